@@ -1,16 +1,10 @@
+use crate::eval::{BinaryOp, UnaryOp};
+use crate::source::Spanned;
 use std::fmt;
 
 impl fmt::Display for Level {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let label = match self {
-            Level::Base => "Base",
-            Level::Medium => "Medium",
-            Level::High => "High",
-            Level::Very => "Very",
-            Level::Extreme => "Extreme",
-            Level::Zenith => "Zenith",
-        };
-        write!(f, "{} ({}-bit)", label, self.bits())
+        write!(f, "{} ({}-bit)", self.name(), self.bits())
     }
 }
 
@@ -32,7 +26,7 @@ impl fmt::Display for Registry {
 pub enum RegistryError {
     AddressOverflow,
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u16)]
 pub enum Level {
     Base = 8,      // 8bits
@@ -63,6 +57,17 @@ impl Level {
         self.bits() / 8
     }
 
+    pub const fn name(self) -> &'static str {
+        match self {
+            Level::Base => "Base",
+            Level::Medium => "Medium",
+            Level::High => "High",
+            Level::Very => "Very",
+            Level::Extreme => "Extreme",
+            Level::Zenith => "Zenith",
+        }
+    }
+
     pub const fn index(self) -> u8 {
         match self {
             Level::Base => 0,
@@ -258,12 +263,49 @@ impl Registry {
 // Une Valeur peut être plusieurs choses dans Maât
 #[derive(Debug, PartialEq, Clone, Hash, Eq)]
 pub enum Expression {
-    Number(i32),
-    Helix { ra: u16, apophis: u16 },
+    Number(i64),
+    Helix {
+        ra: u128,
+        apophis: u128,
+    },
     Register(String),
     Identifier(String),
     StringLiteral(String),
     CurrentAddress,
+    // Un opérande mémoire entre crochets
+    Memory(Box<Address>),
+    // Expressions résolues à l'édition des liens (labels, '$', adresses du Noun)
+    Binary {
+        op: BinaryOp,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expression>,
+    },
+}
+
+/// Un opérande mémoire : `[base + index*échelle + déplacement]`.
+/// Chaque partie est optionnelle ; un déplacement absent vaut 0.
+#[derive(Debug, PartialEq, Clone, Hash, Eq)]
+pub struct Address {
+    pub base: Option<String>,
+    pub index: Option<(String, u8)>,
+    pub displacement: Expression,
+}
+
+impl Expression {
+    /// Vrai si la valeur ne sera connue qu'après la mise en page du binaire.
+    pub fn is_relocatable(&self) -> bool {
+        matches!(
+            self,
+            Expression::Identifier(_)
+                | Expression::CurrentAddress
+                | Expression::Binary { .. }
+                | Expression::Unary { .. }
+        )
+    }
 }
 
 // Les Instructions pures (La Loi)
@@ -273,6 +315,10 @@ pub enum Instruction {
     Sokh {
         destination: String,
     },
+    // wah %registre (Incrémente de 1)
+    Wah {
+        destination: String,
+    },
     CurrentAddress, // Le symbole $
     // nama mon_identifiant = valeur
     Nama {
@@ -300,7 +346,7 @@ pub enum Instruction {
     // smen NOM = VALEUR (Constante de compilation)
     Smen {
         nom: String,
-        valeur: i32,
+        valeur: Expression,
     },
     // out port (Écrit l'octet de %ka vers un port matériel)
     Out {
@@ -311,8 +357,62 @@ pub enum Instruction {
         destination: String,
         value: Expression,
     },
+    // dema "chemin" (relatif à la tablette) ou dema <nom> (dossiers système seulement)
+    // ... as ns : les symboles de la tablette deviennent ns.symbole
     Dema {
         path: String,
+        system: bool,
+        alias: Option<String>,
+    },
+    // export nom, ... : seuls ces symboles sont visibles des tablettes qui importent celle-ci
+    Export(Vec<String>),
+    // private nom, ... : ces symboles restent internes à la tablette
+    Private(Vec<String>),
+    // sesh / msesh / hsesh / vsesh valeur, ... : des données brutes de 8, 16, 32 ou 64 bits
+    Sesh {
+        level: Level,
+        values: Vec<Expression>,
+    },
+    // djed N : aligne l'adresse courante sur un multiple de N
+    Djed {
+        alignment: Expression,
+    },
+    // shu N : réserve N octets à zéro
+    Shu {
+        size: Expression,
+    },
+    // heh N instruction : répète l'instruction N fois
+    Heh {
+        count: Expression,
+        instruction: Box<Spanned<Instruction>>,
+    },
+    // Les blocs de mémoire, de %ib éléments, de %si vers %di (tut, mtut, htut : REP MOVS),
+    // %ka répété dans %di (meh : REP STOS), %si contre %di (sia : REPE CMPS)
+    Tut {
+        level: Level,
+    },
+    Meh {
+        level: Level,
+    },
+    Sia {
+        level: Level,
+    },
+    // ini "fichier" : inclut un fichier binaire tel quel (chemin relatif à la tablette)
+    Ini {
+        path: String,
+    },
+    // Les octets d'un 'ini', lus par le Tisserand
+    Octets(Vec<u8>),
+    // heka nom param, ... (corps) end : une macro, dépliée avant l'émission
+    Heka {
+        nom: String,
+        parametres: Vec<String>,
+        corps: Vec<Spanned<Instruction>>,
+    },
+    // nom argument, ... : l'appel d'une macro heka
+    Invocation {
+        nom: String,
+        arguments: Vec<Expression>,
     },
     // henet %registre, valeur (AND logique)
     Henet {
@@ -324,6 +424,45 @@ pub enum Instruction {
         destination: String,
         value: Expression,
     },
+    // pesh %registre, valeur (XOR logique)
+    Pesh {
+        destination: String,
+        value: Expression,
+    },
+    // maa %registre, valeur (TEST : un AND qui ne garde que les drapeaux)
+    Maa {
+        destination: String,
+        value: Expression,
+    },
+    // kem %registre (NOT : le complément)
+    Kem {
+        destination: String,
+    },
+    // hem %registre (NEG : l'opposé)
+    Hem {
+        destination: String,
+    },
+    // Décalages et rotations : verbe %registre, nombre | %da
+    Wer {
+        destination: String,
+        count: Expression,
+    },
+    Nedjes {
+        destination: String,
+        count: Expression,
+    },
+    NedjesAnkh {
+        destination: String,
+        count: Expression,
+    },
+    Deben {
+        destination: String,
+        count: Expression,
+    },
+    Nebed {
+        destination: String,
+        count: Expression,
+    },
     // Change String en Expression pour tous les sauts
     Neheh {
         target: Expression,
@@ -349,6 +488,47 @@ pub enum Instruction {
     KherAnkh {
         target: Expression,
     },
+    // Les sauts sans signe (adresses, scancodes) et sur les drapeaux
+    Hery {
+        target: Expression,
+    },
+    Khery {
+        target: Expression,
+    },
+    HeryAnkh {
+        target: Expression,
+    },
+    KheryAnkh {
+        target: Expression,
+    },
+    Fai {
+        target: Expression,
+    },
+    NenFai {
+        target: Expression,
+    },
+    Bah {
+        target: Expression,
+    },
+    NenBah {
+        target: Expression,
+    },
+    Tep {
+        target: Expression,
+    },
+    NenTep {
+        target: Expression,
+    },
+    Mety {
+        target: Expression,
+    },
+    NenMety {
+        target: Expression,
+    },
+    // renpet cible : décrémente %ib et saute tant qu'il n'est pas nul (LOOP)
+    Renpet {
+        target: Expression,
+    },
     // duat "Ma phrase", adresse
     Duat {
         phrase: String,
@@ -380,6 +560,15 @@ pub enum Instruction {
         destination: String,
         value: Expression,
     },
+    // wep %ka, diviseur (DIV) et wep_ankh (IDIV) : le quotient dans %ka, le reste dans %da
+    Wep {
+        destination: String,
+        value: Expression,
+    },
+    WepAnkh {
+        destination: String,
+        value: Expression,
+    },
     // Une étiquette dans le code (ex: "boucle:")
     Label(String),
     // wdj %registre, valeur
@@ -401,4 +590,111 @@ pub enum Instruction {
         resultat: Expression,
     },
     Kherp,
+    // nehes vecteur, étiquette : l'étiquette devient le gestionnaire du vecteur dans l'IDT
+    Nehes {
+        vector: Expression,
+        handler: Expression,
+    },
+    // nis N : interruption logicielle (INT N)
+    Nis {
+        vector: Expression,
+    },
+    Iwi,    // Retour d'interruption (IRET)
+    Khetem, // Masque les interruptions (CLI)
+    Wen,    // Démasque les interruptions (STI)
+    Hotep,  // Repos jusqu'à la prochaine interruption (HLT)
+}
+
+impl Instruction {
+    /// Toutes les valeurs portées par l'instruction, pour les passes qui réécrivent l'arbre
+    /// (substitution des constantes `smen`, par exemple).
+    pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+        match self {
+            Instruction::Nama { value, .. }
+            | Instruction::Henek { value, .. }
+            | Instruction::Henet { value, .. }
+            | Instruction::Mer { value, .. }
+            | Instruction::Pesh { value, .. }
+            | Instruction::Maa { value, .. }
+            | Instruction::Kheb { value, .. }
+            | Instruction::Sema { value, .. }
+            | Instruction::Shesa { value, .. }
+            | Instruction::Wep { value, .. }
+            | Instruction::WepAnkh { value, .. } => vec![value],
+            Instruction::Push { target }
+            | Instruction::Dja { target, .. }
+            | Instruction::Neheh { target }
+            | Instruction::Ankh { target }
+            | Instruction::Isfet { target }
+            | Instruction::Jena { target }
+            | Instruction::Her { target }
+            | Instruction::Kher { target }
+            | Instruction::HerAnkh { target }
+            | Instruction::KherAnkh { target }
+            | Instruction::Hery { target }
+            | Instruction::Khery { target }
+            | Instruction::HeryAnkh { target }
+            | Instruction::KheryAnkh { target }
+            | Instruction::Fai { target }
+            | Instruction::NenFai { target }
+            | Instruction::Bah { target }
+            | Instruction::NenBah { target }
+            | Instruction::Tep { target }
+            | Instruction::NenTep { target }
+            | Instruction::Mety { target }
+            | Instruction::NenMety { target }
+            | Instruction::Renpet { target } => vec![target],
+            Instruction::In { port } | Instruction::Out { port } => vec![port],
+            Instruction::Wer { count, .. }
+            | Instruction::Nedjes { count, .. }
+            | Instruction::NedjesAnkh { count, .. }
+            | Instruction::Deben { count, .. }
+            | Instruction::Nebed { count, .. } => vec![count],
+            Instruction::Smen { valeur, .. } => vec![valeur],
+            Instruction::Kheper { adresse, .. } | Instruction::Sena { adresse, .. } => {
+                vec![adresse]
+            }
+            Instruction::Wdj { right, .. } => vec![right],
+            Instruction::Per { message } => vec![message],
+            Instruction::Return { resultat } => vec![resultat],
+            Instruction::Invocation { arguments, .. }
+            | Instruction::Sesh {
+                values: arguments, ..
+            } => arguments.iter_mut().collect(),
+            Instruction::Nehes { vector, handler } => vec![vector, handler],
+            Instruction::Nis { vector } => vec![vector],
+            Instruction::Djed { alignment } => vec![alignment],
+            Instruction::Shu { size } => vec![size],
+            Instruction::Heh { count, instruction } => {
+                let mut expressions = vec![count];
+                expressions.extend(instruction.node.expressions_mut());
+                expressions
+            }
+            Instruction::Sokh { .. }
+            | Instruction::Wah { .. }
+            | Instruction::Tut { .. }
+            | Instruction::Meh { .. }
+            | Instruction::Sia { .. }
+            | Instruction::Kem { .. }
+            | Instruction::Hem { .. }
+            | Instruction::CurrentAddress
+            | Instruction::Rdtsc
+            | Instruction::Pop { .. }
+            | Instruction::Dema { .. }
+            | Instruction::Export(_)
+            | Instruction::Private(_)
+            | Instruction::Heka { .. }
+            | Instruction::Ini { .. }
+            | Instruction::Octets(_)
+            | Instruction::Duat { .. }
+            | Instruction::Label(_)
+            | Instruction::Wab
+            | Instruction::Sedjem { .. }
+            | Instruction::Iwi
+            | Instruction::Khetem
+            | Instruction::Wen
+            | Instruction::Hotep
+            | Instruction::Kherp => Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,139 @@
+use crate::error::{ThotError, ThotResult};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Le cache de construction : pour chaque tablette principale compilée avec les mêmes
+/// options par le même exécutable thot, un manifeste garde l'empreinte blake3 de chaque
+/// fichier lu et celle du binaire. Si aucun fichier n'a changé, le binaire est repris tel
+/// quel au lieu d'être recompilé.
+///
+/// Comme un fichier `.d`, le manifeste ne voit que les fichiers lus : une nouvelle tablette
+/// qui en masquerait une autre dans les dossiers -I ou THOT_PATH n'invalide rien.
+pub struct Cache {
+    dossier: PathBuf,
+    cle: blake3::Hash,
+}
+
+impl Cache {
+    /// `options` : tout ce qui, en dehors des fichiers lus, change le binaire produit.
+    /// Sans exécutable lisible pour l'identifier, le compilateur travaille sans cache.
+    pub fn new(dossier: &Path, principal: &Path, options: &[String]) -> Option<Self> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        // Un thot recompilé garde son numéro de version : c'est l'exécutable qui l'identifie
+        let executable = std::env::current_exe().and_then(fs::read).ok()?;
+        hasher.update(blake3::hash(&executable).as_bytes());
+        // Le manifeste garde les chemins tels que lus : ils dépendent du dossier courant
+        let dossier_courant = std::env::current_dir().unwrap_or_default();
+        let principal = fs::canonicalize(principal).unwrap_or(principal.to_path_buf());
+        let chemins = [
+            dossier_courant.display().to_string(),
+            principal.display().to_string(),
+        ];
+        for morceau in chemins.iter().chain(options) {
+            // La longueur d'abord : ["ab", "c"] et ["a", "bc"] ne se confondent pas
+            hasher.update(&(morceau.len() as u64).to_le_bytes());
+            hasher.update(morceau.as_bytes());
+        }
+        Some(Cache {
+            dossier: dossier.to_path_buf(),
+            cle: hasher.finalize(),
+        })
+    }
+
+    fn manifeste(&self) -> PathBuf {
+        self.dossier.join(format!("{}.manifest", self.cle.to_hex()))
+    }
+
+    fn binaire(&self, empreinte: &str) -> PathBuf {
+        self.dossier.join(format!("{empreinte}.bin"))
+    }
+
+    /// Le binaire de la dernière compilation et les fichiers qu'elle a lus,
+    /// si aucun d'eux n'a changé depuis.
+    pub fn lire(&self) -> Option<(Vec<u8>, Vec<PathBuf>)> {
+        let manifeste = fs::read_to_string(self.manifeste()).ok()?;
+        let mut lignes = manifeste.lines();
+        let empreinte = lignes.next()?.strip_prefix("binary ")?;
+        let mut dependances = Vec::new();
+        for ligne in lignes {
+            let (attendue, chemin) = ligne.split_once(' ')?;
+            let contenu = fs::read(chemin).ok()?;
+            if blake3::hash(&contenu).to_hex().as_str() != attendue {
+                return None;
+            }
+            dependances.push(PathBuf::from(chemin));
+        }
+        let binaire = fs::read(self.binaire(empreinte)).ok()?;
+        // Un binaire du cache abîmé ne doit jamais être livré
+        (blake3::hash(&binaire).to_hex().as_str() == empreinte).then_some((binaire, dependances))
+    }
+
+    /// Range le binaire et le manifeste de cette compilation. Les empreintes sont celles
+    /// des octets que le compilateur a lus : un fichier modifié pendant la compilation
+    /// ne sera pas pris pour la source de ce binaire.
+    pub fn ecrire(
+        &self,
+        binaire: &[u8],
+        dependances: &[(PathBuf, blake3::Hash)],
+    ) -> ThotResult<()> {
+        let erreur = |e: std::io::Error| {
+            ThotError::io(format!(
+                "Failed to write the build cache in {}: {e}",
+                self.dossier.display()
+            ))
+        };
+        let empreinte = blake3::hash(binaire).to_hex();
+        // Un fichier lu deux fois n'apparaît qu'une fois. S'il a changé entre les deux
+        // lectures, aucune empreinte ne décrit le binaire : on ne le range pas.
+        let mut vus = HashMap::new();
+        let mut manifeste = format!("binary {empreinte}\n");
+        for (chemin, hash) in dependances {
+            match vus.insert(chemin, hash) {
+                Some(avant) if avant != hash => return Ok(()),
+                Some(_) => continue,
+                None => {}
+            }
+            let _ = writeln!(manifeste, "{} {}", hash.to_hex(), chemin.display());
+        }
+        fs::create_dir_all(&self.dossier).map_err(erreur)?;
+        fs::write(self.binaire(&empreinte), binaire).map_err(erreur)?;
+        fs::write(self.manifeste(), manifeste).map_err(erreur)
+    }
+}
+
+/// Écrit la règle Make `cible: dépendances`, plus une règle vide par dépendance
+/// (comme `gcc -MP`) pour qu'une tablette supprimée ne bloque pas `make`.
+pub fn write_depfile(chemin: &Path, cible: &str, dependances: &[PathBuf]) -> ThotResult<()> {
+    let mut vues = Vec::new();
+    for dependance in dependances {
+        let dependance = echapper(&dependance.display().to_string());
+        if !vues.contains(&dependance) {
+            vues.push(dependance);
+        }
+    }
+    let mut regle = format!("{}:", echapper(cible));
+    for dependance in &vues {
+        let _ = write!(regle, " \\\n  {dependance}");
+    }
+    regle.push('\n');
+    for dependance in &vues {
+        let _ = write!(regle, "\n{dependance}:\n");
+    }
+    fs::write(chemin, regle).map_err(|e| {
+        ThotError::io(format!(
+            "Failed to write the dependency file {}: {e}",
+            chemin.display()
+        ))
+    })
+}
+
+// Make coupe les noms sur les espaces et lit '$' comme une variable
+fn echapper(chemin: &str) -> String {
+    chemin
+        .replace(' ', "\\ ")
+        .replace('#', "\\#")
+        .replace('$', "$$")
+}
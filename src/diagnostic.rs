@@ -0,0 +1,192 @@
+use crate::error::ThotError;
+use crate::source::{SourceMap, Span};
+use crossterm::execute;
+use crossterm::style::{Print, StyledContent, Stylize};
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Un message destiné au Scribe : erreur fatale ou simple avertissement.
+/// Les deux passent par le même rendu, à la manière de rustc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub title: Option<String>,
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<String>,
+    pub note: Option<(String, Span)>,
+}
+
+impl Diagnostic {
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            title: None,
+            message: message.into(),
+            span: None,
+            help: None,
+            note: None,
+        }
+    }
+
+    pub fn at(mut self, span: Span) -> Self {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Affiche le diagnostic sur stderr :
+    ///
+    /// ```text
+    /// error[size mismatch]: Size mismatch in henek: %ka (Base (8-bit)) vs %hka (High (32-bit))
+    ///  --> os.maat:3:1
+    ///   |
+    /// 3 | henek %ka, %hka
+    ///   | ^^^^^
+    ///   |
+    ///   = help: %hka is 32-bit, use %ka for Base
+    /// ```
+    ///
+    /// Une note éventuelle suit, avec sa propre ligne de la tablette.
+    pub fn render(&self, sources: &SourceMap) -> io::Result<()> {
+        let mut err = io::stderr();
+        let label = match self.severity {
+            Severity::Error => peint("error".red().bold()),
+            Severity::Warning => peint("warning".yellow().bold()),
+        };
+        let title = match &self.title {
+            Some(title) => format!("[{title}]"),
+            None => String::new(),
+        };
+        let header = match self.severity {
+            Severity::Error => peint(title.red().bold()),
+            Severity::Warning => peint(title.yellow().bold()),
+        };
+        execute!(
+            err,
+            Print(label),
+            Print(header),
+            Print(peint(format!(": {}", self.message).bold())),
+            Print("\n")
+        )?;
+
+        // Sans position, on s'arrête au titre (fichier illisible, binaire trop gros...)
+        let Some(span) = self.span else {
+            return Ok(());
+        };
+        let gutter = " ".repeat(span.line.to_string().len());
+        self.extrait(&mut err, sources, span)?;
+
+        if let Some(help) = &self.help {
+            execute!(
+                err,
+                Print(peint(format!("{gutter} |").blue().bold())),
+                Print("\n"),
+                Print(peint(format!("{gutter} = ").blue().bold())),
+                Print(peint("help".bold())),
+                Print(format!(": {help}\n"))
+            )?;
+        }
+        if let Some((note, span)) = &self.note {
+            execute!(
+                err,
+                Print(peint(format!("{gutter} |").blue().bold())),
+                Print("\n"),
+                Print(peint(format!("{gutter} = ").blue().bold())),
+                Print(peint("note".bold())),
+                Print(format!(": {note}\n"))
+            )?;
+            self.extrait(&mut err, sources, *span)?;
+        }
+        execute!(err, Print("\n"))
+    }
+
+    // La position, puis la ligne de la tablette soulignée à la colonne pointée
+    fn extrait(&self, err: &mut io::Stderr, sources: &SourceMap, span: Span) -> io::Result<()> {
+        let line_text = sources
+            .get(span.file)
+            .and_then(|tablet| tablet.source.lines().nth(span.line.saturating_sub(1)));
+        let gutter = " ".repeat(span.line.to_string().len());
+        execute!(
+            err,
+            Print(peint(format!("{gutter}--> ").blue().bold())),
+            Print(sources.locate(span)),
+            Print("\n")
+        )?;
+
+        if let Some(text) = line_text {
+            let (indent, width) = underline(text, span.column);
+            let carets = "^".repeat(width);
+            let carets = match self.severity {
+                Severity::Error => peint(carets.red().bold()),
+                Severity::Warning => peint(carets.yellow().bold()),
+            };
+            execute!(
+                err,
+                Print(peint(format!("{gutter} |").blue().bold())),
+                Print("\n"),
+                Print(peint(format!("{} | ", span.line).blue().bold())),
+                Print(format!("{text}\n")),
+                Print(peint(format!("{gutter} | ").blue().bold())),
+                Print(indent),
+                Print(carets),
+                Print("\n")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&ThotError> for Diagnostic {
+    fn from(erreur: &ThotError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            title: Some(erreur.kind.to_string()),
+            message: erreur.message.clone(),
+            span: erreur.span,
+            help: erreur.help.clone(),
+            note: erreur.note.as_deref().cloned(),
+        }
+    }
+}
+
+/// Les couleurs ne vont qu'à un terminal : redirigé vers un fichier ou un `head`,
+/// ou avec `NO_COLOR`, le diagnostic reste du texte brut.
+pub fn peint<D: Display>(texte: StyledContent<D>) -> String {
+    let couleur = io::stderr().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none_or(|valeur| valeur.is_empty());
+    if couleur {
+        texte.to_string()
+    } else {
+        texte.content().to_string()
+    }
+}
+
+// Calcule l'indentation avant le soulignement (en gardant les tabulations de la
+// ligne pour rester aligné) et la largeur du mot pointé par la colonne.
+fn underline(text: &str, column: usize) -> (String, usize) {
+    let start = column.saturating_sub(1);
+    let indent: String = text
+        .chars()
+        .take(start)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = text
+        .chars()
+        .skip(start)
+        .take_while(|c| !c.is_whitespace() && *c != ',')
+        .count();
+    (indent, width.max(1))
+}
@@ -1,10 +1,15 @@
 use crate::ast::{Expression, Instruction, Level};
+use crate::diagnostic::Diagnostic;
+use crate::error::{ThotError, ThotResult};
+use crate::eval::{evaluate, identifiers};
 use crate::register::{
-    RegBase, RegKind, ensure_helix_fits, ensure_number_fits, ensure_same_level,
-    ensure_supported_level, modrm_imm, modrm_mov_reg_rm, modrm_reg_reg, parse_general_register,
-    parse_register, reg_code, seg_code,
+    Displacement, RegBase, RegKind, ensure_byte_register, ensure_division, ensure_helix_fits,
+    ensure_helix_level, ensure_number_fits, ensure_same_level, ensure_shift_count,
+    ensure_supported_level, imm32, level_prefix, modrm_imm, modrm_memory, modrm_mov_reg_rm,
+    modrm_reg_reg, parse_general_register, parse_register, reg_code, seg_code,
 };
-use std::collections::HashMap;
+use crate::source::{Span, Spanned};
+use std::collections::{HashMap, HashSet};
 const STAGE_ONE: isize = 0x7C00;
 const STAGE_TWO: isize = 0x7E00;
 const NOUN_BASE: u16 = 0xA000;
@@ -20,9 +25,12 @@ const NOUN_HEADER_SIZE: u16 = 0x30;
 const NOUN_TYPE_DATA: u32 = 1;
 const NOUN_PERM_RO: u32 = 1;
 const STACK_TOP: u32 = 0x0009_FC00;
+/// Les routines que Thot ajoute à la fin du Stage 2, en plus de celles préfixées par '__'.
+/// Leurs noms sont réservés : un label du programme les écraserait.
+pub const RUNTIME_ROUTINES: [&str; 2] = ["std_print", "print_hex_32"];
 
 pub struct Emitter {
-    instructions: Vec<Instruction>,
+    instructions: Vec<Spanned<Instruction>>,
     kbd_layout: String,
     in_kernel: bool,
     protected_mode_enabled: bool,
@@ -32,6 +40,459 @@ pub struct Emitter {
     jump: Vec<JumpPatch>,
     cursor_noun: u16,
     labels: HashMap<String, isize>,
+    current_span: Span,
+    // Adresse de chargement de la section en cours (Stage 1 ou Stage 2)
+    section_base: isize,
+    instruction_start: usize,
+    warnings: Vec<Diagnostic>,
+    // Les gestionnaires déclarés par 'nehes' : vecteur, étiquette, position de la déclaration
+    gestionnaires: Vec<(u8, Expression, Span)>,
+}
+
+// Un Helix employé comme adresse ou comme port : seule la force Ra compte,
+// et elle doit tenir dans la largeur de l'encodage.
+fn helix_ra<T: TryFrom<u128>>(context: &str, ra: u128) -> ThotResult<T> {
+    T::try_from(ra).map_err(|_| {
+        ThotError::overflow(format!(
+            "Overflow in {context}: {ra} does not fit in {} bits",
+            std::mem::size_of::<T>() * 8
+        ))
+    })
+}
+
+// Un opérande mémoire prêt à encoder : registres décodés, déplacement connu
+// (Number) ou laissé à l'édition des liens (label, variable, '$')
+struct MemoryOperand {
+    base: Option<RegBase>,
+    index: Option<(RegBase, u8)>,
+    deplacement: Expression,
+}
+
+impl MemoryOperand {
+    fn uses(&self, reg: RegBase) -> bool {
+        self.base == Some(reg) || self.index.is_some_and(|(index, _)| index == reg)
+    }
+}
+
+// Décode l'adresse de sena/kheper : `[...]`, un registre pointeur, ou une adresse directe
+fn memory_operand(context: &str, adresse: &Expression) -> ThotResult<MemoryOperand> {
+    let pointeur = |r: &str| -> ThotResult<RegBase> {
+        let spec = parse_general_register(r)?;
+        ensure_supported_level(context, r, spec.level)?;
+        match spec.kind {
+            RegKind::General(base) => Ok(base),
+            _ => Err(ThotError::syntax(format!(
+                "%{r} cannot be used as a pointer in {context}"
+            ))),
+        }
+    };
+    let operande = match adresse {
+        Expression::Memory(adresse) => MemoryOperand {
+            base: adresse.base.as_deref().map(pointeur).transpose()?,
+            index: match &adresse.index {
+                Some((r, echelle)) => Some((pointeur(r)?, *echelle)),
+                None => None,
+            },
+            deplacement: adresse.displacement.clone(),
+        },
+        Expression::Register(r) => MemoryOperand {
+            base: Some(pointeur(r)?),
+            index: None,
+            deplacement: Expression::Number(0),
+        },
+        Expression::Helix { ra, .. } => MemoryOperand {
+            base: None,
+            index: None,
+            deplacement: Expression::Number(helix_ra::<u32>(context, *ra)? as i64),
+        },
+        Expression::Number(_) => MemoryOperand {
+            base: None,
+            index: None,
+            deplacement: adresse.clone(),
+        },
+        valeur if valeur.is_relocatable() => MemoryOperand {
+            base: None,
+            index: None,
+            deplacement: valeur.clone(),
+        },
+        _ => {
+            return Err(ThotError::syntax(format!(
+                "The address is invalid for {context}."
+            )));
+        }
+    };
+    Ok(operande)
+}
+
+// Au-delà, une réservation ou une répétition ne tiendrait dans aucune image
+const MAX_DATA: usize = 16 << 20;
+
+// Une valeur signée ou non tient-elle sur `taille` octets ?
+fn fits(value: i64, taille: usize) -> bool {
+    if taille >= 8 {
+        return true;
+    }
+    let bits = taille * 8;
+    value >= -(1i64 << (bits - 1)) && value < (1i64 << bits)
+}
+
+// Une donnée brute de `taille` octets, en petit-boutiste
+fn data_bytes(context: &str, value: i64, taille: usize) -> ThotResult<Vec<u8>> {
+    if !fits(value, taille) {
+        return Err(ThotError::overflow(format!(
+            "Overflow in {context}: {value} does not fit in {} bits",
+            taille * 8
+        )));
+    }
+    Ok(value.to_le_bytes()[..taille].to_vec())
+}
+
+// Le nombre d'un djed, shu ou heh : une constante positive, connue avant la mise en page
+fn count(context: &str, value: &Expression) -> ThotResult<usize> {
+    let n = match value {
+        Expression::Number(n) => *n,
+        _ => {
+            return Err(
+                ThotError::syntax(format!("'{context}' requires a constant count"))
+                    .with_help("labels and '$' only get a value after layout"),
+            );
+        }
+    };
+    match usize::try_from(n) {
+        Ok(n) if n <= MAX_DATA => Ok(n),
+        _ => Err(ThotError::overflow(format!(
+            "'{context}' count {n} must be between 0 and {MAX_DATA}"
+        ))),
+    }
+}
+
+// Un port immédiat tient sur 8 bits ; au-delà, il passe par %da
+fn port_imm8(context: &str, port: i64) -> ThotResult<u8> {
+    u8::try_from(port).map_err(|_| {
+        ThotError::overflow(format!(
+            "Overflow in {context}: port {port} does not fit in 8 bits"
+        ))
+        .with_help("load ports above 0xFF into %da")
+    })
+}
+
+// Les décalages et rotations : l'extension /n de C1, D1 et D3, et le nom de la routine
+// qui les porte pour les Helix larges (chaque canal est décalé pour lui-même).
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Decalage {
+    Wer,
+    Nedjes,
+    NedjesAnkh,
+    Deben,
+    Nebed,
+}
+
+impl Decalage {
+    const TOUS: [Decalage; 5] = [
+        Decalage::Wer,
+        Decalage::Nedjes,
+        Decalage::NedjesAnkh,
+        Decalage::Deben,
+        Decalage::Nebed,
+    ];
+
+    fn verbe(self) -> &'static str {
+        match self {
+            Decalage::Wer => "wer",
+            Decalage::Nedjes => "nedjes",
+            Decalage::NedjesAnkh => "nedjes_ankh",
+            Decalage::Deben => "deben",
+            Decalage::Nebed => "nebed",
+        }
+    }
+
+    fn extension(self) -> u8 {
+        match self {
+            Decalage::Deben => 0,      // ROL
+            Decalage::Nebed => 1,      // ROR
+            Decalage::Wer => 4,        // SHL
+            Decalage::Nedjes => 5,     // SHR
+            Decalage::NedjesAnkh => 7, // SAR
+        }
+    }
+
+    fn routine(self) -> &'static str {
+        match self {
+            Decalage::Wer => "shl",
+            Decalage::Nedjes => "shr",
+            Decalage::NedjesAnkh => "sar",
+            Decalage::Deben => "rol",
+            Decalage::Nebed => "ror",
+        }
+    }
+}
+
+// Le nom d'une routine Helix : __helix_xor128 pour Extreme, __zenith_xor256 pour Zenith
+fn helix_routine(operation: &str, level: Level) -> String {
+    if level == Level::Zenith {
+        format!("__zenith_{operation}256")
+    } else {
+        format!("__helix_{operation}128")
+    }
+}
+
+// Les routines bit à bit des Helix larges. EDI pointe le bloc de la destination,
+// ESI celui de la source, ECX porte le compte des décalages. `octets` : 16 ou 32.
+fn routine_xor(octets: u8) -> Vec<u8> {
+    let mut code = vec![0x60]; // PUSHAD
+    for d in (0..octets).step_by(4) {
+        code.extend_from_slice(&[0x8B, 0x47, d]); // MOV EAX, [EDI+d]
+        code.extend_from_slice(&[0x33, 0x46, d]); // XOR EAX, [ESI+d]
+        code.extend_from_slice(&[0x89, 0x47, d]); // MOV [EDI+d], EAX
+    }
+    code.extend_from_slice(&[0x61, 0xC3]); // POPAD, RET
+    code
+}
+
+// ZF = 1 si aucun bit n'est commun aux deux blocs. POPAD et RET laissent les drapeaux.
+fn routine_test(octets: u8) -> Vec<u8> {
+    let mut code = vec![0x60, 0x31, 0xD2]; // PUSHAD, XOR EDX, EDX
+    for d in (0..octets).step_by(4) {
+        code.extend_from_slice(&[0x8B, 0x47, d]); // MOV EAX, [EDI+d]
+        code.extend_from_slice(&[0x23, 0x46, d]); // AND EAX, [ESI+d]
+        code.extend_from_slice(&[0x09, 0xC2]); // OR EDX, EAX
+    }
+    code.extend_from_slice(&[0x61, 0xC3]); // POPAD, RET
+    code
+}
+
+fn routine_not(octets: u8) -> Vec<u8> {
+    let mut code = Vec::new();
+    for d in (0..octets).step_by(4) {
+        code.extend_from_slice(&[0xF7, 0x57, d]); // NOT dword [EDI+d]
+    }
+    code.push(0xC3); // RET
+    code
+}
+
+// Chaque canal devient son opposé : complément, puis +1 propagé par la retenue
+fn routine_neg(octets: u8) -> Vec<u8> {
+    let canal = octets / 2;
+    let mut code = Vec::new();
+    for debut in [0, canal] {
+        for d in (debut..debut + canal).step_by(4) {
+            code.extend_from_slice(&[0xF7, 0x57, d]); // NOT dword [EDI+d]
+        }
+        code.extend_from_slice(&[0x83, 0x47, debut, 0x01]); // ADD dword [EDI+debut], 1
+        for d in (debut + 4..debut + canal).step_by(4) {
+            code.extend_from_slice(&[0x83, 0x57, d, 0x00]); // ADC dword [EDI+d], 0
+        }
+    }
+    code.push(0xC3); // RET
+    code
+}
+
+// Décale chaque canal d'un bit, ECX fois : le bit qui sort d'un dword entre
+// dans le suivant par la retenue (RCL/RCR).
+fn routine_decalage(octets: u8, decalage: Decalage) -> Vec<u8> {
+    let canal = octets / 2;
+    let mut corps = Vec::new();
+    for debut in [0, canal] {
+        let haut = debut + canal - 4;
+        let montee = (debut..debut + canal).step_by(4);
+        let descente = (debut..=haut).rev().step_by(4);
+        match decalage {
+            Decalage::Wer => {
+                corps.extend_from_slice(&[0xD1, 0x67, debut]); // SHL dword [EDI+debut], 1
+                for d in montee.skip(1) {
+                    corps.extend_from_slice(&[0xD1, 0x57, d]); // RCL dword [EDI+d], 1
+                }
+            }
+            Decalage::Nedjes | Decalage::NedjesAnkh => {
+                let modrm = if decalage == Decalage::Nedjes {
+                    0x6F
+                } else {
+                    0x7F
+                };
+                corps.extend_from_slice(&[0xD1, modrm, haut]); // SHR/SAR dword [EDI+haut], 1
+                for d in descente.skip(1) {
+                    corps.extend_from_slice(&[0xD1, 0x5F, d]); // RCR dword [EDI+d], 1
+                }
+            }
+            Decalage::Deben => {
+                corps.extend_from_slice(&[0x8B, 0x47, haut]); // MOV EAX, [EDI+haut]
+                corps.extend_from_slice(&[0xD1, 0xE0]); // SHL EAX, 1 (CF = bit de tête)
+                for d in montee {
+                    corps.extend_from_slice(&[0xD1, 0x57, d]); // RCL dword [EDI+d], 1
+                }
+            }
+            Decalage::Nebed => {
+                corps.extend_from_slice(&[0x8B, 0x47, debut]); // MOV EAX, [EDI+debut]
+                corps.extend_from_slice(&[0xD1, 0xE8]); // SHR EAX, 1 (CF = bit de queue)
+                for d in descente {
+                    corps.extend_from_slice(&[0xD1, 0x5F, d]); // RCR dword [EDI+d], 1
+                }
+            }
+        }
+    }
+    let boucle = corps.len() as u8 + 2;
+    let mut code = vec![
+        0x60, // PUSHAD
+        0x83,
+        0xE1,
+        canal * 8 - 1, // AND ECX, bits du canal - 1
+        0xE3,
+        boucle, // JECXZ fin
+    ];
+    code.extend_from_slice(&corps);
+    code.extend_from_slice(&[0xE2, boucle.wrapping_neg()]); // LOOP corps
+    code.extend_from_slice(&[0x61, 0xC3]); // fin : POPAD, RET
+    code
+}
+
+// Un saut court (rel8) si la distance le permet, sa forme longue sinon : E9 rel32 pour
+// JMP (EB), 0F 8x rel32 pour un saut conditionnel. `distance` se compte depuis la fin du
+// saut court : en arrière, la forme longue s'éloigne de sa cible des octets qu'elle ajoute.
+fn saut_relatif(opcode: u8, distance: isize) -> Vec<u8> {
+    if let Ok(court) = i8::try_from(distance) {
+        return vec![opcode, court as u8];
+    }
+    let mut saut = if opcode == 0xEB {
+        vec![0xE9]
+    } else {
+        vec![0x0F, opcode + 0x10]
+    };
+    let ajoutes = saut.len() as isize + 2;
+    let long = if distance < 0 {
+        distance - ajoutes
+    } else {
+        distance
+    };
+    saut.extend_from_slice(&(long as i32).to_le_bytes());
+    saut
+}
+
+// Un saut par-dessus `code`, s'il est pris
+fn sauter_si(condition: u8, code: &[u8]) -> Vec<u8> {
+    let mut saut = saut_relatif(condition, code.len() as isize);
+    saut.extend_from_slice(code);
+    saut
+}
+
+// CALL rel32 vers une position de la même routine
+fn appel(code: &mut Vec<u8>, cible: usize) {
+    let distance = cible as isize - (code.len() + 5) as isize;
+    code.push(0xE8);
+    code.extend_from_slice(&(distance as i32).to_le_bytes());
+}
+
+// Division longue non signée d'un canal, bit par bit. EDI pointe le dividende (puis le
+// quotient), ESI le diviseur, EDX le reste. Le dividende glisse dans le reste, et chaque
+// fois que le reste contient le diviseur, on l'en retire et le bit du quotient vaut 1.
+// Un diviseur nul donne un quotient plein et laisse le dividende en reste (saturation).
+fn division_canal(canal: u8) -> Vec<u8> {
+    let dwords = (0..canal).step_by(4);
+    let mut code = vec![0x31, 0xC0]; // XOR EAX, EAX
+    for d in dwords.clone() {
+        code.extend_from_slice(&[0x89, 0x42, d]); // MOV [EDX+d], EAX (reste = 0)
+    }
+    code.extend_from_slice(&[0xB1, canal * 8]); // MOV CL, bits du canal
+    let boucle = code.len();
+    code.extend_from_slice(&[0xD1, 0x67, 0x00]); // SHL dword [EDI], 1
+    for d in dwords.clone().skip(1) {
+        code.extend_from_slice(&[0xD1, 0x57, d]); // RCL dword [EDI+d], 1
+    }
+    for d in dwords.clone() {
+        code.extend_from_slice(&[0xD1, 0x52, d]); // RCL dword [EDX+d], 1
+    }
+    // Le bit sorti du reste : s'il vaut 1, le reste dépasse forcément le diviseur
+    code.extend_from_slice(&[0x0F, 0x92, 0xC5]); // SETC CH
+    let mut ajout = Vec::new();
+    for (i, d) in dwords.enumerate() {
+        let (sub, add) = if i == 0 { (0x29, 0x01) } else { (0x19, 0x11) };
+        code.extend_from_slice(&[0x8B, 0x46, d]); // MOV EAX, [ESI+d]
+        code.extend_from_slice(&[sub, 0x42, d]); // SUB/SBB [EDX+d], EAX
+        ajout.extend_from_slice(&[0x8B, 0x46, d]); // MOV EAX, [ESI+d]
+        ajout.extend_from_slice(&[add, 0x42, d]); // ADD/ADC [EDX+d], EAX
+    }
+    // Le retrait échoue s'il emprunte sans bit sorti : CH - CF < 0. Il est alors rendu.
+    code.extend_from_slice(&[0x80, 0xDD, 0x00]); // SBB CH, 0
+    let mut pose = vec![0x83, 0x4F, 0x00, 0x01]; // OR dword [EDI], 1
+    pose.extend_from_slice(&saut_relatif(0xEB, ajout.len() as isize)); // JMP suivant
+    code.extend_from_slice(&sauter_si(0x78, &pose)); // JS ajout
+    code.extend_from_slice(&ajout);
+    code.extend_from_slice(&[0xFE, 0xC9]); // suivant : DEC CL
+    let retour = boucle as isize - (code.len() + 2) as isize;
+    code.extend_from_slice(&saut_relatif(0x75, retour)); // JNZ boucle
+    code
+}
+
+// __helix_div128 / __helix_idiv128 (et leurs sœurs Zenith) : chaque canal de [EDI]
+// est divisé par le canal de [ESI], le reste va dans [EDX]. Le canal est une
+// sous-routine, appelée une fois pour Ra et une fois pour Apophis.
+fn routine_division(octets: u8, signee: bool) -> Vec<u8> {
+    let canal = octets / 2;
+    // Le canal de [EAX] devient son opposé, si ZF = 0 à l'appel
+    let mut negation = Vec::new();
+    if signee {
+        let mut corps = Vec::new();
+        for d in (0..canal).step_by(4) {
+            corps.extend_from_slice(&[0xF7, 0x50, d]); // NOT dword [EAX+d]
+        }
+        corps.extend_from_slice(&[0x83, 0x40, 0x00, 0x01]); // ADD dword [EAX], 1
+        for d in (4..canal).step_by(4) {
+            corps.extend_from_slice(&[0x83, 0x50, d, 0x00]); // ADC dword [EAX+d], 0
+        }
+        negation = sauter_si(0x74, &corps); // JZ fin
+        negation.push(0xC3); // fin : RET
+    }
+    // PUSHAD, CALL, trois ADD, CALL, POPAD et RET : 22 octets avant les sous-routines
+    let opposer = 22;
+    let sous_routine = opposer + negation.len();
+    let mut code = vec![0x60]; // PUSHAD
+    appel(&mut code, sous_routine); // Ra
+    code.extend_from_slice(&[0x83, 0xC7, canal]); // ADD EDI, canal
+    code.extend_from_slice(&[0x83, 0xC6, canal]); // ADD ESI, canal
+    code.extend_from_slice(&[0x83, 0xC2, canal]); // ADD EDX, canal
+    appel(&mut code, sous_routine); // Apophis
+    code.extend_from_slice(&[0x61, 0xC3]); // POPAD, RET
+    code.extend_from_slice(&negation);
+
+    let haut = canal - 1; // l'octet du bit de signe
+    if signee {
+        // Division des valeurs absolues ; BL et BH gardent les signes du dividende et du
+        // diviseur. Un diviseur nul ne compte pas le signe du dividende (saturation).
+        code.extend_from_slice(&[0x8B, 0x46, 0x00]); // MOV EAX, [ESI]
+        for d in (4..canal).step_by(4) {
+            code.extend_from_slice(&[0x0B, 0x46, d]); // OR EAX, [ESI+d]
+        }
+        code.extend_from_slice(&[0x0F, 0x95, 0xC5]); // SETNZ CH
+        code.extend_from_slice(&[0xF6, 0x47, haut, 0x80]); // TEST byte [EDI+haut], 0x80
+        code.extend_from_slice(&[0x0F, 0x95, 0xC3, 0x20, 0xEB]); // SETNZ BL ; AND BL, CH
+        code.extend_from_slice(&[0x89, 0xF8]); // MOV EAX, EDI
+        appel(&mut code, opposer);
+        code.extend_from_slice(&[0xF6, 0x46, haut, 0x80]); // TEST byte [ESI+haut], 0x80
+        code.extend_from_slice(&[0x0F, 0x95, 0xC7]); // SETNZ BH
+        code.extend_from_slice(&[0x89, 0xF0]); // MOV EAX, ESI
+        appel(&mut code, opposer);
+    }
+    code.extend_from_slice(&division_canal(canal));
+    if signee {
+        // MOV ne touche pas les drapeaux : chaque test décide de l'appel qui le suit
+        code.extend_from_slice(&[0x84, 0xFF, 0x89, 0xF0]); // TEST BH, BH ; MOV EAX, ESI
+        appel(&mut code, opposer); // le diviseur est rendu intact
+        code.extend_from_slice(&[0x88, 0xD8, 0x30, 0xF8]); // MOV AL, BL ; XOR AL, BH
+        code.extend_from_slice(&[0x89, 0xF8]); // MOV EAX, EDI
+        appel(&mut code, opposer); // le quotient prend le signe du produit des signes
+        code.extend_from_slice(&[0x84, 0xDB, 0x89, 0xD0]); // TEST BL, BL ; MOV EAX, EDX
+        appel(&mut code, opposer); // le reste suit le dividende
+    }
+    code.push(0xC3); // RET
+    code
+}
+
+// Comment un emplacement se remplit une fois les adresses connues
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PatchKind {
+    // Distance depuis la fin de l'emplacement (sauts et appels)
+    Relative,
+    // Valeur brute de l'expression (immédiats, adresses)
+    Absolute,
 }
 
 #[derive(Clone)]
@@ -40,6 +501,10 @@ struct JumpPatch {
     target: Expression,
     kernel: bool,
     size: usize,
+    span: Span,
+    kind: PatchKind,
+    // Début de l'instruction dans son buffer : la valeur de '$'
+    origin: usize,
 }
 
 impl Emitter {
@@ -55,13 +520,30 @@ impl Emitter {
             jump: Vec::new(),
             cursor_noun: NOUN_BASE,
             labels: HashMap::new(),
+            current_span: Span::default(),
+            section_base: STAGE_ONE,
+            instruction_start: 0,
+            warnings: Vec::new(),
+            gestionnaires: Vec::new(),
         }
     }
-    pub fn add_instruction(&mut self, instruction: Vec<Instruction>) -> &mut Self {
+    pub fn add_instruction(&mut self, instruction: Vec<Spanned<Instruction>>) -> &mut Self {
         self.instructions.extend(instruction);
         self
     }
 
+    /// Avertissements accumulés pendant la génération, à afficher même si elle échoue.
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, message: impl Into<String>, help: impl Into<String>) {
+        let warning = Diagnostic::warning(message)
+            .at(self.current_span)
+            .with_help(help);
+        self.warnings.push(warning);
+    }
+
     fn emit_mov_reg_reg(&self, code: &mut Vec<u8>, dest: RegBase, src: RegBase) {
         if dest == src {
             return;
@@ -90,6 +572,98 @@ impl Emitter {
         }
     }
 
+    // Les verbes de bloc : CLD, puis REP (REPE pour CMPS) sur %ib éléments. L'opcode
+    // octet (A4, AA, A6) devient celui du mot ou du dword à l'octet suivant.
+    // En Mode Réel, 0x67 fait compter ECX et avancer ESI/EDI, comme en Mode Protégé.
+    fn emit_bloc(&self, code: &mut Vec<u8>, level: Level, opcode: u8) {
+        if opcode == 0xA6 {
+            // REPE CMPS ne touche pas les drapeaux pour un bloc vide : vide = égal
+            self.emit_op32_prefix(code);
+            code.extend_from_slice(&[0x85, 0xC9]); // TEST ECX, ECX
+        }
+        code.push(0xFC); // CLD
+        self.emit_operand_size(code, level);
+        if !self.protected_mode_enabled {
+            code.push(0x67);
+        }
+        code.push(0xF3); // REP / REPE
+        code.push(if level == Level::Base {
+            opcode
+        } else {
+            opcode + 1
+        });
+    }
+
+    // `opcode reg, [mémoire]` en adressage 32 bits. En Mode Réel, 0x66 (données 32 bits)
+    // et 0x67 (adressage 32 bits, le seul qui connaisse le SIB) précèdent l'opcode.
+    fn emit_memory(
+        &mut self,
+        code: &mut Vec<u8>,
+        context: &str,
+        opcode: u8,
+        reg: u8,
+        operande: &MemoryOperand,
+    ) -> ThotResult<()> {
+        if !self.protected_mode_enabled {
+            code.extend_from_slice(&[0x66, 0x67]);
+        }
+        code.push(opcode);
+        let connu = match operande.deplacement {
+            Expression::Number(n) => Some(n),
+            _ => None,
+        };
+        let (modrm, taille) = modrm_memory(reg, operande.base, operande.index, connu)?;
+        code.extend_from_slice(&modrm);
+        match (taille, connu) {
+            (Displacement::None, _) => {}
+            (Displacement::Byte(d), _) => code.push(d as u8),
+            (Displacement::Dword, Some(n)) => {
+                code.extend_from_slice(&imm32(context, n)?.to_le_bytes());
+            }
+            // Label ou variable : l'adresse sera posée après la mise en page
+            (Displacement::Dword, None) => self.record_absolute(code, &operande.deplacement),
+        }
+        Ok(())
+    }
+
+    // Charge l'adresse d'un opérande mémoire dans `dest` (les pointeurs ESI/EDI des
+    // copies 128 bits). Les formes simples gardent MOV ; le reste passe par LEA.
+    fn emit_load_address(
+        &mut self,
+        code: &mut Vec<u8>,
+        context: &str,
+        dest: RegBase,
+        operande: &MemoryOperand,
+    ) -> ThotResult<()> {
+        match (operande.base, operande.index, &operande.deplacement) {
+            (Some(base), None, Expression::Number(0)) => self.emit_mov_reg_reg(code, dest, base),
+            (None, None, Expression::Number(n)) => {
+                self.emit_mov_reg_imm32(code, dest, imm32(context, *n)?);
+            }
+            (None, None, valeur) => {
+                self.emit_op32_prefix(code);
+                code.push(0xB8 + reg_code(dest)); // MOV r32, imm32
+                self.record_absolute(code, valeur);
+            }
+            _ => self.emit_memory(code, context, 0x8D, reg_code(dest), operande)?, // LEA
+        }
+        Ok(())
+    }
+
+    // La taille d'opérande d'un niveau jusqu'à High. 0x66 bascule la taille par défaut :
+    // 16 bits en Mode Protégé, 32 en Mode Réel. Base n'en a pas besoin, ses opcodes
+    // sont ceux de l'octet.
+    fn emit_operand_size(&self, code: &mut Vec<u8>, level: Level) {
+        let bascule = match level {
+            Level::Medium => self.protected_mode_enabled,
+            Level::High => !self.protected_mode_enabled,
+            _ => false,
+        };
+        if bascule {
+            code.push(0x66);
+        }
+    }
+
     fn emit_op32_prefix(&self, code: &mut Vec<u8>) {
         if !self.protected_mode_enabled {
             code.push(0x66);
@@ -103,17 +677,31 @@ impl Emitter {
 
     fn record_jump(&mut self, code: &mut Vec<u8>, target: &Expression) {
         let size = if self.protected_mode_enabled { 4 } else { 2 };
+        self.record_patch(code, target.clone(), size, PatchKind::Relative);
+    }
+
+    // Un immédiat de 32 bits dont la valeur dépend de la mise en page (labels, '$', Noun)
+    fn record_absolute(&mut self, code: &mut Vec<u8>, value: &Expression) {
+        self.record_patch(code, value.clone(), 4, PatchKind::Absolute);
+    }
+
+    fn record_patch(
+        &mut self,
+        code: &mut Vec<u8>,
+        target: Expression,
+        size: usize,
+        kind: PatchKind,
+    ) {
         self.jump.push(JumpPatch {
             offset: code.len(),
-            target: target.clone(),
+            target,
             kernel: self.in_kernel,
             size,
+            span: self.current_span,
+            kind,
+            origin: self.instruction_start,
         });
-        if size == 4 {
-            code.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
-        } else {
-            code.extend_from_slice(&[0x00, 0x00]);
-        }
+        code.extend(std::iter::repeat_n(0x00, size));
     }
 
     fn emit_pmode_prologue(&self, base_addr: isize) -> (Vec<u8>, usize, usize, usize) {
@@ -190,29 +778,58 @@ impl Emitter {
         (code, lgdt_off, lidt_off, pmode_entry_off)
     }
 
-    fn alloc_helix_literal(&mut self, level: Level, ra: u16, apophis: u16) -> u16 {
-        if level != Level::Extreme {
-            panic!("Helix literal storage is only supported for Extreme (128) right now.");
+    // Range un littéral Helix large (Extreme, Zenith) dans le Noun, à pleine précision :
+    // Ra occupe la première moitié du bloc, Apophis la seconde.
+    fn alloc_helix_literal(
+        &mut self,
+        context: &str,
+        reg: &str,
+        level: Level,
+        ra: u128,
+        apophis: u128,
+    ) -> ThotResult<u16> {
+        if level < Level::Extreme {
+            return Err(ThotError::size_mismatch(format!(
+                "Helix literal storage is only used for Extreme and Zenith registers: %{reg} ({level})"
+            )));
         }
+        ensure_helix_fits(context, reg, level, ra, apophis)?;
+        let channel = level.bytes() as usize / 2;
         let mut block = vec![0u8; level.bytes() as usize];
-        let ra64 = (ra as u64).to_le_bytes();
-        let ap64 = (apophis as u64).to_le_bytes();
-        block[0..8].copy_from_slice(&ra64);
-        block[8..16].copy_from_slice(&ap64);
-        self.alloc_noun_object(NOUN_TYPE_DATA, &block, 0)
-    }
-
-    fn alloc_zenith_literal(&mut self, ra: u16, apophis: u16) -> u16 {
-        let mut block = vec![0u8; Level::Zenith.bytes() as usize];
-        let ra64 = (ra as u64).to_le_bytes();
-        let ap64 = (apophis as u64).to_le_bytes();
-        block[0..8].copy_from_slice(&ra64);
-        block[8..16].copy_from_slice(&ap64);
-        // Reste du bloc (16..32) = 0
-        self.alloc_noun_object(NOUN_TYPE_DATA, &block, 0)
-    }
-    pub fn sokh(&mut self, actual_code: &mut Vec<u8>, destination: &str) {
-        let dest_spec = parse_general_register(destination);
+        block[..channel].copy_from_slice(&ra.to_le_bytes()[..channel]);
+        block[channel..].copy_from_slice(&apophis.to_le_bytes()[..channel]);
+        Ok(self.alloc_noun_object(NOUN_TYPE_DATA, &block, 0))
+    }
+
+    pub fn wah(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
+        let dest_base = match dest_spec.kind {
+            RegKind::General(base) => base,
+            _ => unreachable!(),
+        };
+
+        if dest_spec.level <= Level::High {
+            // Les drapeaux (ZF au passage à zéro, OF) sont ceux de la largeur du registre
+            ensure_byte_register("wah", destination, dest_spec.level, dest_base)?;
+            if dest_spec.level == Level::Base {
+                actual_code.push(0xFE); // INC r/m8
+                actual_code.push(modrm_imm(dest_base, 0));
+            } else {
+                self.emit_operand_size(actual_code, dest_spec.level);
+                // L'OpCode INC registre commence à 0x40
+                actual_code.push(0x40 + reg_code(dest_base));
+            }
+        } else {
+            return Err(ThotError::size_mismatch(format!(
+                "For the moment, Wah does not know how to raise registers beyond High (32-bit): %{} ({})",
+                destination, dest_spec.level
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn sokh(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
@@ -220,17 +837,18 @@ impl Emitter {
 
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(actual_code); // Protection 32 bits
-            ensure_supported_level("sokh", destination, dest_spec.level);
+            ensure_supported_level("sokh", destination, dest_spec.level)?;
 
             // L'OpCode DEC registre commence à 0x48
             let opcode = 0x48 + reg_code(dest_base);
             actual_code.push(opcode);
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "For the moment, Sokh does not know how to reduce registers beyond High (32-bit): %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
         }
+        Ok(())
     }
 
     fn alloc_noun_object(&mut self, obj_type: u32, payload: &[u8], entrypoint: u32) -> u16 {
@@ -257,7 +875,7 @@ impl Emitter {
         self.cursor_noun = payload_addr + payload.len() as u16;
         payload_addr
     }
-    pub fn kherankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn kherankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         // OpCode pour JLE (Jump if Less or Equal)
         self.emit_rel16_prefix(actual_code);
         actual_code.push(0x0F);
@@ -265,8 +883,9 @@ impl Emitter {
 
         // On enregistre l'emplacement pour le patcher plus tard
         self.record_jump(actual_code, target);
+        Ok(())
     }
-    pub fn herankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn herankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         // OpCode pour JGE (Jump if Greater or Equal)
         self.emit_rel16_prefix(actual_code);
         actual_code.push(0x0F);
@@ -274,8 +893,9 @@ impl Emitter {
 
         // On enregistre l'emplacement pour le patcher plus tard
         self.record_jump(actual_code, target);
+        Ok(())
     }
-    pub fn ankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn ankh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         // OpCode pour JE near (Sauter si Égal, relatif 16-bit)
         self.emit_rel16_prefix(actual_code);
         actual_code.push(0x0F);
@@ -283,8 +903,9 @@ impl Emitter {
 
         // On enregistre l'endroit à patcher (comme pour neheh)
         self.record_jump(actual_code, target);
+        Ok(())
     }
-    pub fn per(&mut self, actual_code: &mut Vec<u8>, message: &Expression) {
+    pub fn per(&mut self, actual_code: &mut Vec<u8>, message: &Expression) -> ThotResult<()> {
         match message {
             Expression::StringLiteral(s) => {
                 let mut payload = s.as_bytes().to_vec();
@@ -316,7 +937,7 @@ impl Emitter {
                 }
             }
             Expression::Register(r) => {
-                let spec = parse_general_register(r);
+                let spec = parse_general_register(r)?;
 
                 // On vérifie si c'est bien %ka (Base)
                 if spec.kind == RegKind::General(RegBase::Ka) && spec.level == Level::Base {
@@ -341,63 +962,78 @@ impl Emitter {
                         ]);
                     }
                 } else {
-                    panic!("The Scribe only knows how to manifest %ka at the moment.");
+                    return Err(ThotError::syntax(
+                        "The Scribe only knows how to manifest %ka at the moment.",
+                    ));
                 }
             }
-            _ => panic!("Unknown message type for the verb 'per'."),
+            _ => {
+                return Err(ThotError::syntax(
+                    "Unknown message type for the verb 'per'.",
+                ));
+            }
         }
+        Ok(())
     }
-    pub fn mer(&mut self, actual_code: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_general_register(destination);
+    pub fn mer(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(actual_code);
-            ensure_supported_level("mer", destination, dest_spec.level);
+            ensure_supported_level("mer", destination, dest_spec.level)?;
             match value {
                 Expression::Number(n) => {
                     actual_code.push(0x81);
                     actual_code.push(modrm_imm(dest_base, 1));
-                    ensure_number_fits("mer", destination, dest_spec.level, *n);
-                    actual_code.extend_from_slice(&n.to_le_bytes());
+                    ensure_number_fits("mer", destination, dest_spec.level, *n)?;
+                    actual_code.extend_from_slice(&(*n as u32).to_le_bytes());
+                }
+                expr if expr.is_relocatable() => {
+                    actual_code.push(0x81);
+                    actual_code.push(modrm_imm(dest_base, 1));
+                    self.record_absolute(actual_code, expr);
                 }
                 Expression::Helix { ra, apophis } => {
-                    ensure_helix_fits(
-                        "mer",
-                        destination,
-                        dest_spec.level,
-                        *ra as u128,
-                        *apophis as u128,
-                    );
+                    ensure_helix_fits("mer", destination, dest_spec.level, *ra, *apophis)?;
                     let n = ((*ra as i32) << 16) | (*apophis as i32);
                     actual_code.push(0x81);
                     actual_code.push(modrm_imm(dest_base, 1));
                     actual_code.extend_from_slice(&n.to_le_bytes());
                 }
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("mer", destination, dest_spec.level, src, src_spec.level);
-                    ensure_supported_level("mer", src, src_spec.level);
+                    ensure_same_level("mer", destination, dest_spec.level, src, src_spec.level)?;
+                    ensure_supported_level("mer", src, src_spec.level)?;
                     actual_code.push(0x09); // OR r/m32, r32
                     actual_code.push(modrm_reg_reg(dest_base, src_base));
                 }
-                _ => panic!("Mer only supports numbers, Helix literals, or registers."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Mer only supports numbers, Helix literals, or registers.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Extreme {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("mer", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("mer", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(actual_code, RegBase::Si, src_base);
                     self.emit_rel16_prefix(actual_code);
@@ -406,7 +1042,13 @@ impl Emitter {
                     self.record_jump(actual_code, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "mer",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(actual_code);
@@ -414,66 +1056,79 @@ impl Emitter {
                     let target = Expression::Identifier("__helix_or128".to_string());
                     self.record_jump(actual_code, &target);
                 }
-                _ => panic!("Mer only supports Helix literals or registers for 128-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Mer only supports Helix literals or registers for 128-bit.",
+                    ));
+                }
             }
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Mer does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
         }
+        Ok(())
     }
-    pub fn henet(&mut self, actual_code: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_general_register(destination);
+    pub fn henet(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(actual_code); // Stabilisation 32 bits
-            ensure_supported_level("henet", destination, dest_spec.level);
+            ensure_supported_level("henet", destination, dest_spec.level)?;
             match value {
                 Expression::Number(n) => {
                     actual_code.push(0x81); // Opcode groupe logique
                     actual_code.push(modrm_imm(dest_base, 4));
-                    ensure_number_fits("henet", destination, dest_spec.level, *n);
-                    actual_code.extend_from_slice(&n.to_le_bytes());
+                    ensure_number_fits("henet", destination, dest_spec.level, *n)?;
+                    actual_code.extend_from_slice(&(*n as u32).to_le_bytes());
+                }
+                expr if expr.is_relocatable() => {
+                    actual_code.push(0x81); // Opcode groupe logique
+                    actual_code.push(modrm_imm(dest_base, 4));
+                    self.record_absolute(actual_code, expr);
                 }
                 Expression::Helix { ra, apophis } => {
-                    ensure_helix_fits(
-                        "henet",
-                        destination,
-                        dest_spec.level,
-                        *ra as u128,
-                        *apophis as u128,
-                    );
+                    ensure_helix_fits("henet", destination, dest_spec.level, *ra, *apophis)?;
                     let n = ((*ra as i32) << 16) | (*apophis as i32);
                     actual_code.push(0x81);
                     actual_code.push(modrm_imm(dest_base, 4));
                     actual_code.extend_from_slice(&n.to_le_bytes());
                 }
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("henet", destination, dest_spec.level, src, src_spec.level);
-                    ensure_supported_level("henet", src, src_spec.level);
+                    ensure_same_level("henet", destination, dest_spec.level, src, src_spec.level)?;
+                    ensure_supported_level("henet", src, src_spec.level)?;
                     actual_code.push(0x21); // AND r/m32, r32
                     actual_code.push(modrm_reg_reg(dest_base, src_base));
                 }
-                _ => panic!("Henet only supports numbers, Helix literals, or registers."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Henet only supports numbers, Helix literals, or registers.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Extreme {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("henet", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("henet", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(actual_code, RegBase::Si, src_base);
                     self.emit_rel16_prefix(actual_code);
@@ -482,7 +1137,13 @@ impl Emitter {
                     self.record_jump(actual_code, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "henet",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(actual_code);
@@ -490,39 +1151,298 @@ impl Emitter {
                     let target = Expression::Identifier("__helix_and128".to_string());
                     self.record_jump(actual_code, &target);
                 }
-                _ => panic!("Henet only supports Helix literals or registers for 128-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Henet only supports Helix literals or registers for 128-bit.",
+                    ));
+                }
             }
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Henet does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
+            )));
+        }
+        Ok(())
+    }
+    fn emit_call_routine(&mut self, code: &mut Vec<u8>, routine: String) {
+        self.emit_rel16_prefix(code);
+        code.push(0xE8);
+        self.record_jump(code, &Expression::Identifier(routine));
+    }
+
+    // pesh (XOR) et maa (TEST) : même forme, seuls les opcodes changent. Jusqu'à High,
+    // l'opération a la largeur du niveau : les drapeaux (ZF, et SF que lit tep) sont ceux
+    // de %ka, %mka ou %hka. Extreme et Zenith passent par leur routine.
+    fn bitwise(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        verbe: &str,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        // (registre à registre, immédiat /n, routine), dans leur forme dword
+        let (opcode, (opcode_imm, extension), operation) = if verbe == "pesh" {
+            (0x31, (0x81, 6), "xor") // XOR r/m32, r32 ; XOR r/m32, imm32
+        } else {
+            (0x85, (0xF7, 0), "test") // TEST r/m32, r32 ; TEST r/m32, imm32
+        };
+        let dest_spec = parse_general_register(destination)?;
+        let dest_base = match dest_spec.kind {
+            RegKind::General(base) => base,
+            _ => unreachable!(),
+        };
+        if dest_spec.level <= Level::High {
+            let level = dest_spec.level;
+            ensure_byte_register(verbe, destination, level, dest_base)?;
+            // XOR r/m8 (30, 80 /6) et TEST r/m8 (84, F6 /0) précèdent leur forme dword ;
+            // l'immédiat de 81 /6 passe à 80 /6, celui de F7 /0 à F6 /0
+            let (opcode, opcode_imm) = if level == Level::Base {
+                (opcode - 1, if opcode_imm == 0x81 { 0x80 } else { 0xF6 })
+            } else {
+                (opcode, opcode_imm)
+            };
+            let taille = level.bytes() as usize;
+            self.emit_operand_size(actual_code, level);
+            match value {
+                Expression::Number(n) => {
+                    ensure_number_fits(verbe, destination, level, *n)?;
+                    actual_code.push(opcode_imm);
+                    actual_code.push(modrm_imm(dest_base, extension));
+                    actual_code.extend_from_slice(&n.to_le_bytes()[..taille]);
+                }
+                expr if expr.is_relocatable() => {
+                    actual_code.push(opcode_imm);
+                    actual_code.push(modrm_imm(dest_base, extension));
+                    self.record_patch(actual_code, expr.clone(), taille, PatchKind::Absolute);
+                }
+                Expression::Helix { ra, apophis } => {
+                    ensure_helix_fits(verbe, destination, level, *ra, *apophis)?;
+                    let n = (*ra << (level.bits() / 2)) | *apophis;
+                    actual_code.push(opcode_imm);
+                    actual_code.push(modrm_imm(dest_base, extension));
+                    actual_code.extend_from_slice(&n.to_le_bytes()[..taille]);
+                }
+                Expression::Register(src) => {
+                    let src_spec = parse_general_register(src)?;
+                    let src_base = match src_spec.kind {
+                        RegKind::General(base) => base,
+                        _ => unreachable!(),
+                    };
+                    ensure_same_level(verbe, destination, level, src, src_spec.level)?;
+                    ensure_byte_register(verbe, src, level, src_base)?;
+                    actual_code.push(opcode);
+                    actual_code.push(modrm_reg_reg(dest_base, src_base));
+                }
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "'{verbe}' only supports numbers, Helix literals, or registers."
+                    )));
+                }
+            }
+        } else {
+            ensure_helix_level(verbe, destination, dest_spec.level)?;
+            match value {
+                Expression::Register(src) => {
+                    let src_spec = parse_general_register(src)?;
+                    let src_base = match src_spec.kind {
+                        RegKind::General(base) => base,
+                        _ => unreachable!(),
+                    };
+                    ensure_same_level(verbe, destination, dest_spec.level, src, src_spec.level)?;
+                    self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
+                    self.emit_mov_reg_reg(actual_code, RegBase::Si, src_base);
+                }
+                Expression::Helix { ra, apophis } => {
+                    let addr = self.alloc_helix_literal(
+                        verbe,
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
+                    self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
+                    self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
+                }
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "'{verbe}' only supports Helix literals or registers for Extreme and Zenith."
+                    )));
+                }
+            }
+            self.emit_call_routine(actual_code, helix_routine(operation, dest_spec.level));
+        }
+        Ok(())
+    }
+
+    pub fn pesh(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        self.bitwise(actual_code, "pesh", destination, value)
+    }
+
+    pub fn maa(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        self.bitwise(actual_code, "maa", destination, value)
+    }
+
+    // kem (NOT, 0xF7 /2) et hem (NEG, 0xF7 /3), 0xF6 pour un octet
+    fn complement(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        verbe: &str,
+        destination: &str,
+        extension: u8,
+        operation: &str,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
+        let dest_base = match dest_spec.kind {
+            RegKind::General(base) => base,
+            _ => unreachable!(),
+        };
+        if dest_spec.level <= Level::High {
+            ensure_byte_register(verbe, destination, dest_spec.level, dest_base)?;
+            self.emit_operand_size(actual_code, dest_spec.level);
+            actual_code.push(if dest_spec.level == Level::Base {
+                0xF6
+            } else {
+                0xF7
+            });
+            actual_code.push(modrm_imm(dest_base, extension));
+        } else {
+            ensure_helix_level(verbe, destination, dest_spec.level)?;
+            self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
+            self.emit_call_routine(actual_code, helix_routine(operation, dest_spec.level));
+        }
+        Ok(())
+    }
+
+    pub fn kem(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        self.complement(actual_code, "kem", destination, 2, "not")
+    }
+
+    pub fn hem(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        self.complement(actual_code, "hem", destination, 3, "neg")
+    }
+
+    // Le compte vient d'un nombre ou de %da. x86 ne décale que de CL : ECX et EDX
+    // s'échangent le temps du décalage, ce qui ne change aucun autre registre.
+    fn decaler(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        decalage: Decalage,
+        destination: &str,
+        count: &Expression,
+    ) -> ThotResult<()> {
+        let verbe = decalage.verbe();
+        let dest_spec = parse_general_register(destination)?;
+        let dest_base = match dest_spec.kind {
+            RegKind::General(base) => base,
+            _ => unreachable!(),
+        };
+        ensure_helix_level(verbe, destination, dest_spec.level)?;
+        ensure_shift_count(verbe, destination, dest_spec.level, count)?;
+        if dest_spec.level <= Level::High {
+            let level = dest_spec.level;
+            ensure_byte_register(verbe, destination, level, dest_base)?;
+            // D0, C0 et D2 décalent un octet ; D1, C1 et D3 le mot ou le dword
+            let mot = u8::from(level != Level::Base);
+            match count {
+                Expression::Number(1) => {
+                    self.emit_operand_size(actual_code, level);
+                    actual_code.push(0xD0 + mot); // décalage d'un bit
+                    actual_code.push(modrm_imm(dest_base, decalage.extension()));
+                }
+                Expression::Number(n) => {
+                    self.emit_operand_size(actual_code, level);
+                    actual_code.push(0xC0 + mot); // décalage de imm8 bits
+                    actual_code.push(modrm_imm(dest_base, decalage.extension()));
+                    actual_code.push(*n as u8);
+                }
+                Expression::Register(_) => {
+                    // La destination suit l'échange si c'était %ib ou %da
+                    let cible = match dest_base {
+                        RegBase::Ib => RegBase::Da,
+                        RegBase::Da => RegBase::Ib,
+                        autre => autre,
+                    };
+                    self.emit_op32_prefix(actual_code);
+                    actual_code.extend_from_slice(&[0x87, 0xD1]); // XCHG ECX, EDX
+                    self.emit_operand_size(actual_code, level);
+                    actual_code.push(0xD2 + mot); // décalage de CL bits
+                    actual_code.push(modrm_imm(cible, decalage.extension()));
+                    self.emit_op32_prefix(actual_code);
+                    actual_code.extend_from_slice(&[0x87, 0xD1]); // XCHG ECX, EDX
+                }
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "'{verbe}' count must be a number or %da."
+                    )));
+                }
+            }
+        } else {
+            // La routine lit le compte dans ECX : on le garde autour de l'appel
+            self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
+            self.emit_op32_prefix(actual_code);
+            actual_code.push(0x51); // PUSH ECX
+            match count {
+                Expression::Number(n) => {
+                    self.emit_mov_reg_imm32(actual_code, RegBase::Ib, *n as u32);
+                }
+                Expression::Register(_) => {
+                    self.emit_mov_reg_reg(actual_code, RegBase::Ib, RegBase::Da);
+                }
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "'{verbe}' count must be a number or %da."
+                    )));
+                }
+            }
+            self.emit_call_routine(
+                actual_code,
+                helix_routine(decalage.routine(), dest_spec.level),
             );
+            self.emit_op32_prefix(actual_code);
+            actual_code.push(0x59); // POP ECX
         }
+        Ok(())
     }
-    pub fn kheb(&mut self, actual_code: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_general_register(destination);
+
+    pub fn kheb(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(actual_code); // Stabilisation 32 bits
-            ensure_supported_level("kheb", destination, dest_spec.level);
+            ensure_supported_level("kheb", destination, dest_spec.level)?;
             match value {
                 Expression::Number(n) => {
                     actual_code.push(0x81); // SUB r/m32, imm32
                     actual_code.push(modrm_imm(dest_base, 5));
-                    ensure_number_fits("kheb", destination, dest_spec.level, *n);
-                    actual_code.extend_from_slice(&n.to_le_bytes());
+                    ensure_number_fits("kheb", destination, dest_spec.level, *n)?;
+                    actual_code.extend_from_slice(&(*n as u32).to_le_bytes());
+                }
+                expr if expr.is_relocatable() => {
+                    actual_code.push(0x81); // SUB r/m32, imm32
+                    actual_code.push(modrm_imm(dest_base, 5));
+                    self.record_absolute(actual_code, expr);
                 }
                 Expression::Helix { ra, apophis } => {
-                    ensure_helix_fits(
-                        "kheb",
-                        destination,
-                        dest_spec.level,
-                        *ra as u128,
-                        *apophis as u128,
-                    );
+                    ensure_helix_fits("kheb", destination, dest_spec.level, *ra, *apophis)?;
                     let n = ((*ra as i32) << 16) | (*apophis as i32);
                     actual_code.push(0x81);
                     actual_code.push(modrm_imm(dest_base, 5));
@@ -532,28 +1452,32 @@ impl Emitter {
                     // MODE 2 : Soustraire un Registre (SUB r/m32, reg32)
                     actual_code.push(0x29); // OpCode SUB registre à registre
 
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level);
-                    ensure_supported_level("kheb", src, src_spec.level);
+                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level)?;
+                    ensure_supported_level("kheb", src, src_spec.level)?;
 
                     let modrm = modrm_reg_reg(dest_base, src_base);
                     actual_code.push(modrm);
                 }
-                _ => panic!("Kheb only supports numbers, Helix literals, or registers."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Kheb only supports numbers, Helix literals, or registers.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Extreme {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(actual_code, RegBase::Si, src_base);
                     self.emit_rel16_prefix(actual_code);
@@ -562,7 +1486,13 @@ impl Emitter {
                     self.record_jump(actual_code, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "kheb",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(actual_code);
@@ -570,17 +1500,21 @@ impl Emitter {
                     let target = Expression::Identifier("__helix_sub128".to_string());
                     self.record_jump(actual_code, &target);
                 }
-                _ => panic!("Kheb only supports Helix literals or registers for 128-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Kheb only supports Helix literals or registers for 128-bit.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Zenith {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("kheb", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(actual_code, RegBase::Si, src_base);
                     self.emit_rel16_prefix(actual_code);
@@ -589,7 +1523,13 @@ impl Emitter {
                     self.record_jump(actual_code, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_zenith_literal(*ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "kheb",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(actual_code, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(actual_code);
@@ -597,16 +1537,26 @@ impl Emitter {
                     let target = Expression::Identifier("__zenith_sub256".to_string());
                     self.record_jump(actual_code, &target);
                 }
-                _ => panic!("Kheb only supports Helix literals or registers for 256-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Kheb only supports Helix literals or registers for 256-bit.",
+                    ));
+                }
             }
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Kheb does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
         }
+        Ok(())
     }
-    pub fn duat(&mut self, actual_code: &mut Vec<u8>, phrase: &str, address: &u16) {
+    pub fn duat(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        phrase: &str,
+        address: &u16,
+    ) -> ThotResult<()> {
         for (i, c) in phrase.chars().enumerate() {
             // Opcode 0xC6 0x06 = MOV [imm16], imm8
             actual_code.push(0xC6);
@@ -621,14 +1571,15 @@ impl Emitter {
         let addr_zero = address + phrase.len() as u16;
         actual_code.extend_from_slice(&addr_zero.to_le_bytes());
         actual_code.push(0x00);
+        Ok(())
     }
-    pub fn setjem(&mut self, actual_code: &mut Vec<u8>, destination: &str) {
-        let dest_spec = parse_general_register(destination);
-        ensure_supported_level("sedjem", destination, dest_spec.level);
+    pub fn setjem(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
+        ensure_supported_level("sedjem", destination, dest_spec.level)?;
 
         if let RegKind::General(RegBase::Ka) = dest_spec.kind {
             if dest_spec.level != Level::Base {
-                panic!("Sedjem only supports %ka (Base).");
+                return Err(ThotError::size_mismatch("Sedjem only supports %ka (Base)."));
             }
 
             if self.protected_mode_enabled {
@@ -649,6 +1600,7 @@ impl Emitter {
                 ]);
             }
         }
+        Ok(())
     }
     pub fn set_kbd_layout(&mut self, layout: String) -> &mut Self {
         self.kbd_layout = layout;
@@ -658,144 +1610,106 @@ impl Emitter {
         self.in_kernel = in_kernel;
         self
     }
-    pub fn neheh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn neheh(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         self.emit_rel16_prefix(actual_code);
         actual_code.push(0xE9);
         self.record_jump(actual_code, target);
+        Ok(())
     }
-    pub fn jena(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn jena(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         actual_code.push(0xE8);
         self.record_jump(actual_code, target);
+        Ok(())
     }
-    pub fn io_in(&mut self, actual_code: &mut Vec<u8>, port: &Expression) {
+    pub fn io_in(&mut self, actual_code: &mut Vec<u8>, port: &Expression) -> ThotResult<()> {
         // Lecture matérielle (toujours vers AL - 8 bits)
         match port {
             Expression::Helix { ra, .. } => {
-                // IN imm8 ou OUT imm8
-                actual_code.push(0xE4); // ou 0xE6 pour le Out
-                actual_code.push(*ra as u8); // On ne prend que 8 bits de Ra
+                actual_code.push(0xE4); // IN AL, imm8
+                actual_code.push(helix_ra::<u8>("in", *ra)?); // On ne prend que 8 bits de Ra
+            }
+            Expression::Number(n) => {
+                actual_code.push(0xE4); // IN AL, imm8
+                actual_code.push(port_imm8("in", *n)?);
             }
             Expression::Register(r) => {
-                let reg_spec = parse_general_register(r);
-                ensure_supported_level("in", r, reg_spec.level);
+                let reg_spec = parse_general_register(r)?;
+                ensure_supported_level("in", r, reg_spec.level)?;
                 if let RegKind::General(RegBase::Da) = reg_spec.kind {
                     if reg_spec.level != Level::Base {
-                        panic!("The IN port must be %da (Base).");
+                        return Err(ThotError::size_mismatch("The IN port must be %da (Base)."));
                     }
                     // IN AL, DX (Lit le port contenu dans %da)
                     actual_code.push(0xEC);
                 } else {
-                    panic!("The IN port must be a number or register %da");
+                    return Err(ThotError::syntax(
+                        "The IN port must be a number or register %da",
+                    ));
                 }
             }
-            _ => panic!("The IN port must be a number or register %da"),
+            _ => {
+                return Err(ThotError::syntax(
+                    "The IN port must be a number or register %da",
+                ));
+            }
         }
+        Ok(())
     }
-    pub fn wab(&mut self, actual_code: &mut Vec<u8>) {
+    pub fn wab(&mut self, actual_code: &mut Vec<u8>) -> ThotResult<()> {
         actual_code.extend_from_slice(&[0xB8, 0x03, 0x00, 0xCD, 0x10]);
+        Ok(())
     }
 
-    pub fn isfet(&mut self, code_actual: &mut Vec<u8>, target: &Expression) {
+    pub fn isfet(&mut self, code_actual: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         // OpCode pour JNE near (Sauter si Différent, relatif 16-bit)
         self.emit_rel16_prefix(code_actual);
         code_actual.push(0x0F);
         code_actual.push(0x85);
         // On enregistre l'endroit à patcher EXACTEMENT comme pour ankh
         self.record_jump(code_actual, target);
+        Ok(())
     }
-    pub fn kheper(&mut self, code_actual: &mut Vec<u8>, source: &str, adresse: &Expression) {
+    pub fn kheper(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        source: &str,
+        adresse: &Expression,
+    ) -> ThotResult<()> {
         // 1. On identifie le code du registre source
-        let source_spec = parse_general_register(source);
+        let source_spec = parse_general_register(source)?;
         let source_base = match source_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
+        let operande = memory_operand("kheper", adresse)?;
 
         if source_spec.level == Level::Extreme {
             // Copier 16 octets depuis l'adresse pointée par le registre source vers la RAM
-            self.emit_mov_reg_reg(code_actual, RegBase::Si, source_base);
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Di, *ra as u32);
-                }
-                Expression::Number(n) => {
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Di, *n as u32);
-                }
-                Expression::Identifier(nom) => {
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{nom}' not found"));
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Di, *addr as u32);
-                }
-                Expression::Register(r) => {
-                    let ptr_spec = parse_general_register(r);
-                    ensure_supported_level("kheper", r, ptr_spec.level);
-                    if let RegKind::General(RegBase::Ba) = ptr_spec.kind {
-                        self.emit_mov_reg_reg(code_actual, RegBase::Di, RegBase::Ba);
-                    } else {
-                        panic!("The write address is invalid for kheper.");
-                    }
-                }
-                _ => panic!("The destination address is invalid for kheper."),
+            if source_base != RegBase::Si && operande.uses(RegBase::Si) {
+                return Err(ThotError::syntax(
+                    "The destination address of a 128-bit kheper cannot use %hsi: it holds the source pointer",
+                ));
             }
+            self.emit_mov_reg_reg(code_actual, RegBase::Si, source_base);
+            self.emit_load_address(code_actual, "kheper", RegBase::Di, &operande)?;
             self.emit_rep_movsd_4(code_actual);
-            return;
+            return Ok(());
         }
 
-        ensure_supported_level("kheper", source, source_spec.level);
-        let reg_code: u8 = reg_code(source_base);
-        if self.protected_mode_enabled {
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    code_actual.push(0x89);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*ra as u32).to_le_bytes());
-                }
-                Expression::Number(n) => {
-                    code_actual.push(0x89);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*n as u32).to_le_bytes());
-                }
-                Expression::Identifier(nom) => {
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{nom}' not found"));
-                    code_actual.push(0x89);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*addr as u32).to_le_bytes());
-                }
-                _ => panic!("The destination address is invalid for kheper."),
-            }
-        } else {
-            // Préfixes pour le Mode Réel : 32 bits data, 16 bits addr
-            code_actual.push(0x66);
-            code_actual.push(0x67);
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    code_actual.push(0x89); // ou 0x8B
-                    code_actual.push(0x06 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*ra).to_le_bytes()); // Seulement 16 bits
-                }
-                Expression::Identifier(nom) => {
-                    // On récupère l'adresse de la variable résolue par Thot
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{nom}' not found"));
-                    code_actual.push(0x89);
-                    code_actual.push(0x06 | (reg_code << 3));
-                    code_actual.extend_from_slice(addr.to_le_bytes().as_slice());
-                }
-                _ => panic!("The destination address is invalid for kheper."),
-            }
-        }
+        ensure_supported_level("kheper", source, source_spec.level)?;
+        // MOV [mémoire], r32
+        self.emit_memory(
+            code_actual,
+            "kheper",
+            0x89,
+            reg_code(source_base),
+            &operande,
+        )
     }
-    pub fn push(&mut self, actual_code: &mut Vec<u8>, target: &Expression) {
+    pub fn push(&mut self, actual_code: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         match target {
             Expression::Register(r) => {
-                let reg_spec = parse_general_register(r);
+                let reg_spec = parse_general_register(r)?;
                 let reg_base = match reg_spec.kind {
                     RegKind::General(base) => base,
                     _ => unreachable!(),
@@ -817,10 +1731,10 @@ impl Emitter {
                     }
                     self.emit_rep_movsd_4(actual_code);
                 } else if reg_spec.level == Level::Zenith {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Push does not yet support registers beyond Extreme: %{} ({})",
                         r, reg_spec.level
-                    );
+                    )));
                 } else {
                     self.emit_op32_prefix(actual_code); // Protection 32-bit
                     // L'OpCode PUSH registre commence à 0x50
@@ -831,12 +1745,27 @@ impl Emitter {
             Expression::Number(n) => {
                 self.emit_op32_prefix(actual_code); // Protection 32-bit
                 actual_code.push(0x68); // OpCode PUSH imm32
-                actual_code.extend_from_slice(&n.to_le_bytes());
+                actual_code.extend_from_slice(&imm32("push", *n)?.to_le_bytes());
+            }
+            expr if expr.is_relocatable() => {
+                self.emit_op32_prefix(actual_code); // Protection 32-bit
+                actual_code.push(0x68); // OpCode PUSH imm32
+                self.record_absolute(actual_code, expr);
+            }
+            _ => {
+                return Err(ThotError::syntax(
+                    "Push only supports registers, numbers and addresses.",
+                ));
             }
-            _ => panic!("Push only supports registers and numbers."),
         }
+        Ok(())
     }
-    pub fn dja(&mut self, actual_code: &mut Vec<u8>, segment: u16, target: &Expression) {
+    pub fn dja(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        segment: u16,
+        target: &Expression,
+    ) -> ThotResult<()> {
         // Dans la boucle generer_instructions :
         // 1. L'Opcode du Far Call (0x9A)s
         actual_code.push(0x9A);
@@ -847,128 +1776,131 @@ impl Emitter {
 
         // 3. Le Sélecteur de Segment (2 octets)
         actual_code.extend_from_slice(&segment.to_le_bytes());
+        Ok(())
     }
     fn record_jump_far(&mut self, code: &mut Vec<u8>, target: Expression) {
-        self.jump.push(JumpPatch {
-            offset: code.len(),
-            target,
-            kernel: self.in_kernel,
-            size: 4, // Toujours 4 octets pour l'offset du Far Call en 32 bits
-        });
-        code.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        // Toujours 4 octets pour l'offset du Far Call en 32 bits
+        self.record_patch(code, target, 4, PatchKind::Relative);
     }
-    pub fn sena(&mut self, code_actual: &mut Vec<u8>, destination: &str, adresse: &Expression) {
-        let dest_spec = parse_general_register(destination);
+    pub fn sena(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        destination: &str,
+        adresse: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
+        let operande = memory_operand("sena", adresse)?;
+
         if dest_spec.level == Level::Extreme {
             // Copier 16 octets depuis la RAM vers l'adresse pointée par le registre destination
-            self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Si, *ra as u32);
-                }
-                Expression::Number(n) => {
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Si, *n as u32);
-                }
-                Expression::Identifier(nom) => {
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{}' introuvable", nom));
-                    self.emit_mov_reg_imm32(code_actual, RegBase::Si, *addr as u32);
-                }
-                Expression::Register(r) => {
-                    let ptr_spec = parse_general_register(r);
-                    ensure_supported_level("sena", r, ptr_spec.level);
-                    if let RegKind::General(RegBase::Ba) = ptr_spec.kind {
-                        self.emit_mov_reg_reg(code_actual, RegBase::Si, RegBase::Ba);
-                    } else {
-                        panic!("The read address is invalid for Thoth.");
-                    }
-                }
-                _ => panic!("The read address is invalid for Thoth."),
+            if dest_base != RegBase::Di && operande.uses(RegBase::Di) {
+                return Err(ThotError::syntax(
+                    "The source address of a 128-bit sena cannot use %hdi: it holds the destination pointer",
+                ));
             }
+            self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
+            self.emit_load_address(code_actual, "sena", RegBase::Si, &operande)?;
             self.emit_rep_movsd_4(code_actual);
-            return;
+            return Ok(());
         }
 
-        ensure_supported_level("sena", destination, dest_spec.level);
-        // 1. On identifie le code du registre target
-        let reg_code: u8 = reg_code(dest_base);
-        if self.protected_mode_enabled {
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    code_actual.push(0x8B);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*ra as u32).to_le_bytes());
-                }
+        ensure_supported_level("sena", destination, dest_spec.level)?;
+        // MOV r32, [mémoire]
+        self.emit_memory(code_actual, "sena", 0x8B, reg_code(dest_base), &operande)
+    }
+    // sesh / msesh / hsesh / vsesh : des valeurs brutes au fil du code
+    pub fn sesh(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        level: Level,
+        values: &[Expression],
+    ) -> ThotResult<()> {
+        let verbe = format!("{}sesh", level_prefix(level));
+        let taille = level.bits() as usize / 8;
+        for valeur in values {
+            match valeur {
                 Expression::Number(n) => {
-                    code_actual.push(0x8B);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*n as u32).to_le_bytes());
+                    code_actual.extend_from_slice(&data_bytes(&verbe, *n, taille)?);
                 }
-                Expression::Identifier(nom) => {
-                    // On récupère l'adresse de la variable (SLS ou NAMA)
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{}' introuvable", nom));
-                    code_actual.push(0x8B);
-                    code_actual.push(0x05 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*addr as u32).to_le_bytes());
-                }
-                Expression::Register(r) => {
-                    let ptr_spec = parse_general_register(r);
-                    ensure_supported_level("sena", r, ptr_spec.level);
-                    if let RegKind::General(RegBase::Ba) = ptr_spec.kind {
-                        // Cas particulier : sena %reg, [%ba]
-                        code_actual.push(0x8B);
-                        code_actual.push(0x07 | (reg_code << 3));
-                    } else {
-                        panic!("The read address is invalid for Thoth.");
-                    }
+                Expression::StringLiteral(texte) if level == Level::Base => {
+                    code_actual.extend_from_slice(texte.as_bytes());
                 }
-                _ => panic!("The read address is invalid for Thoth."),
-            }
-        } else {
-            // On prépare le terrain : Mode 32 bits et Adressage 16 bits
-            code_actual.push(0x66);
-            code_actual.push(0x67);
-            match adresse {
-                Expression::Helix { ra, .. } => {
-                    code_actual.push(0x89); // ou 0x8B
-                    code_actual.push(0x06 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*ra).to_le_bytes()); // Seulement 16 bits
-                }
-                Expression::Identifier(nom) => {
-                    // On récupère l'adresse de la variable (SLS ou NAMA)
-                    let addr = self
-                        .variables
-                        .get(nom)
-                        .unwrap_or_else(|| panic!("Variable '{nom}' not found"));
-                    code_actual.push(0x8B);
-                    code_actual.push(0x06 | (reg_code << 3));
-                    code_actual.extend_from_slice(&(*addr).to_le_bytes());
-                }
-                Expression::Register(r) => {
-                    let ptr_spec = parse_general_register(r);
-                    ensure_supported_level("sena", r, ptr_spec.level);
-                    if let RegKind::General(RegBase::Ba) = ptr_spec.kind {
-                        // Cas particulier : sena %reg, [%ba]
-                        code_actual.push(0x8B);
-                        code_actual.push(0x07 | (reg_code << 3));
-                    } else {
-                        panic!("The read address is invalid for Thoth.");
-                    }
+                Expression::StringLiteral(_) => {
+                    return Err(ThotError::syntax(format!("'{verbe}' cannot hold a string"))
+                        .with_help("strings are bytes: use 'sesh'"));
+                }
+                // Label, variable ou '$' : l'adresse sera posée après la mise en page
+                valeur if valeur.is_relocatable() => {
+                    self.record_patch(code_actual, valeur.clone(), taille, PatchKind::Absolute);
+                }
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "'{verbe}' only takes numbers, strings and addresses"
+                    )));
                 }
-                _ => panic!("The read address is invalid for Thoth."),
             }
         }
+        Ok(())
     }
-    pub fn kherp(&mut self, code_actual: &mut Vec<u8>) {
+
+    // djed N : bourre de NOP jusqu'à une adresse multiple de N (le bourrage reste exécutable)
+    pub fn djed(&mut self, code_actual: &mut Vec<u8>, alignment: &Expression) -> ThotResult<()> {
+        let n = count("djed", alignment)?;
+        if !n.is_power_of_two() {
+            return Err(ThotError::syntax(format!(
+                "'djed' aligns on a power of two, not on {n}"
+            )));
+        }
+        let adresse = self.section_base as usize + code_actual.len();
+        let fin = code_actual.len() + (adresse.next_multiple_of(n) - adresse);
+        code_actual.resize(fin, 0x90);
+        Ok(())
+    }
+
+    // Un vecteur d'interruption : une constante entre 0 et 255
+    fn vecteur(context: &str, vector: &Expression) -> ThotResult<u8> {
+        match vector {
+            Expression::Number(n) => u8::try_from(*n).map_err(|_| {
+                ThotError::overflow(format!("'{context}' vector {n} must be between 0 and 255"))
+            }),
+            _ => Err(ThotError::syntax(format!(
+                "'{context}' requires a constant interrupt vector"
+            ))),
+        }
+    }
+
+    // nehes N, étiquette : rien n'est émis, l'IDT est construite après la mise en page
+    fn nehes(&mut self, vector: &Expression, handler: Expression) -> ThotResult<()> {
+        let n = Self::vecteur("nehes", vector)?;
+        if let Some((_, _, premier)) = self.gestionnaires.iter().find(|(v, ..)| *v == n) {
+            return Err(
+                ThotError::syntax(format!("Interrupt vector {n} already has a handler"))
+                    .with_note("first bound here", *premier),
+            );
+        }
+        self.gestionnaires.push((n, handler, self.current_span));
+        Ok(())
+    }
+
+    // heh N instruction : chaque copie a son propre '$'
+    fn heh(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        fois: &Expression,
+        instruction: &Spanned<Instruction>,
+    ) -> ThotResult<()> {
+        for _ in 0..count("heh", fois)? {
+            self.instruction_start = code_actual.len();
+            self.emit_instruction(code_actual, instruction.node.clone())?;
+        }
+        Ok(())
+    }
+
+    pub fn kherp(&mut self, code_actual: &mut Vec<u8>) -> ThotResult<()> {
         let setup_disque = vec![
             0xB8, 0x40, 0x02, // AH=02 (Lecture), AL=0x40 (On lit 64 secteurs = 32 Ko !)
             0xBB, 0x00, 0x7E, // Destination en RAM : 0x7E00
@@ -977,9 +1909,10 @@ impl Emitter {
             0xCD, 0x13, // Appel BIOS
         ];
         code_actual.extend_from_slice(&setup_disque);
+        Ok(())
     }
-    pub fn pop(&mut self, actual_code: &mut Vec<u8>, destination: &str) {
-        let dest_spec = parse_general_register(destination);
+    pub fn pop(&mut self, actual_code: &mut Vec<u8>, destination: &str) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
@@ -1001,57 +1934,99 @@ impl Emitter {
                 actual_code.extend_from_slice(&[0x66, 0x83, 0xC4, 0x10]);
             }
         } else if dest_spec.level == Level::Zenith {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Pop does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
         } else {
             self.emit_op32_prefix(actual_code); // Protection 32-bit
             // L'OpCode POP registre commence à 0x58
             let opcode = 0x58 + reg_code(dest_base);
             actual_code.push(opcode);
         }
+        Ok(())
     }
-    pub fn io_out(&mut self, actual_code: &mut Vec<u8>, port: &Expression) {
+    pub fn io_out(&mut self, actual_code: &mut Vec<u8>, port: &Expression) -> ThotResult<()> {
         // Écriture matérielle (toujours depuis AL - 8 bits)
         match port {
             Expression::Helix { ra, .. } => {
-                // IN imm8 ou OUT imm8
-                actual_code.push(0xE4); // ou 0xE6 pour le Out
-                actual_code.push(*ra as u8); // On ne prend que 8 bits de Ra
+                actual_code.push(0xE6); // OUT imm8, AL
+                actual_code.push(helix_ra::<u8>("out", *ra)?); // On ne prend que 8 bits de Ra
+            }
+            Expression::Number(n) => {
+                actual_code.push(0xE6); // OUT imm8, AL
+                actual_code.push(port_imm8("out", *n)?);
             }
             Expression::Register(r) => {
-                let reg_spec = parse_general_register(r);
-                ensure_supported_level("out", r, reg_spec.level);
+                let reg_spec = parse_general_register(r)?;
+                ensure_supported_level("out", r, reg_spec.level)?;
                 if let RegKind::General(RegBase::Da) = reg_spec.kind {
                     if reg_spec.level != Level::Base {
-                        panic!("The OUT port must be %da (Base).");
+                        return Err(ThotError::size_mismatch("The OUT port must be %da (Base)."));
                     }
                     // OUT DX, AL (Écrit vers le port contenu dans %da)
                     actual_code.push(0xEE);
                 } else {
-                    panic!("The OUT port must be a number or register %da");
+                    return Err(ThotError::syntax(
+                        "The OUT port must be a number or register %da",
+                    ));
                 }
             }
-            _ => panic!("The OUT port must be a number or register %da"),
+            _ => {
+                return Err(ThotError::syntax(
+                    "The OUT port must be a number or register %da",
+                ));
+            }
         }
+        Ok(())
     }
-    pub fn her(&mut self, code_actual: &mut Vec<u8>, target: &Expression) {
+    pub fn her(&mut self, code_actual: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         self.emit_rel16_prefix(code_actual);
         code_actual.push(0x0F);
         code_actual.push(0x8F); // OpCode pour JG (Saut si plus grand)
         self.record_jump(code_actual, target);
+        Ok(())
     }
-    pub fn kher(&mut self, code_actual: &mut Vec<u8>, target: &Expression) {
+    pub fn kher(&mut self, code_actual: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
         self.emit_rel16_prefix(code_actual);
         code_actual.push(0x0F);
         code_actual.push(0x8C); // OpCode pour JL (Saut si plus petit)
         self.record_jump(code_actual, target);
+        Ok(())
     }
-    pub fn nama(&mut self, name: &str, value: &Expression) {
+    // Les autres sauts conditionnels : 0x0F, puis le code de la condition
+    fn saut_si(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        condition: u8,
+        target: &Expression,
+    ) -> ThotResult<()> {
+        self.emit_rel16_prefix(code_actual);
+        code_actual.push(0x0F);
+        code_actual.push(condition);
+        self.record_jump(code_actual, target);
+        Ok(())
+    }
+    pub fn renpet(&mut self, code_actual: &mut Vec<u8>, target: &Expression) -> ThotResult<()> {
+        // LOOP ne saute qu'à 127 octets : il saute sur un JMP proche, qui atteint la cible.
+        // Les drapeaux ne bougent pas, comme avec LOOP seul.
+        let jmp_proche = if self.protected_mode_enabled {
+            5
+        } else {
+            code_actual.push(0x67); // LOOP compte sur ECX, pas sur CX
+            3
+        };
+        code_actual.extend_from_slice(&[0xE2, 0x02]); // LOOP +2
+        code_actual.extend_from_slice(&[0xEB, jmp_proche]); // JMP court : la boucle est finie
+        code_actual.push(0xE9); // JMP proche vers la cible
+        self.record_jump(code_actual, target);
+        Ok(())
+    }
+    pub fn nama(&mut self, name: &str, value: &Expression) -> ThotResult<()> {
         let contenu_brut = match value {
             Expression::Helix { ra, apophis } => {
-                let n = ((*ra as i32) << 16) | (*apophis as i32);
+                let n = (helix_ra::<u16>("nama", *ra)? as u32) << 16
+                    | helix_ra::<u16>("nama", *apophis)? as u32;
                 n.to_le_bytes().to_vec()
             }
             Expression::StringLiteral(s) => {
@@ -1059,58 +2034,78 @@ impl Emitter {
                 b.push(0); // Signe du Silence
                 b
             }
-            Expression::Number(n) => n.to_le_bytes().to_vec(),
-            _ => panic!("Type not supported in the Noun."),
+            Expression::Number(n) => imm32("nama", *n)?.to_le_bytes().to_vec(),
+            _ => return Err(ThotError::syntax("Type not supported in the Noun.")),
         };
         let address = self.alloc_noun_object(NOUN_TYPE_DATA, &contenu_brut, 0);
         self.variables.insert(name.to_string(), address);
+        Ok(())
     }
-    pub fn henek(&mut self, code: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_register(destination);
+    pub fn henek(
+        &mut self,
+        code: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_register(destination)?;
         match dest_spec.kind {
             RegKind::Segment(seg) => {
                 if let Expression::Register(src) = value {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     if src_spec.level != Level::Base {
-                        panic!("Segment moves require base registers: %{src}");
+                        return Err(ThotError::size_mismatch(format!(
+                            "Segment moves require base registers: %{src}"
+                        )));
                     }
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_supported_level("henek", src, src_spec.level);
+                    ensure_supported_level("henek", src, src_spec.level)?;
                     code.push(0x8E); // MOV sreg, r/m16
                     code.push(0xC0 | (seg_code(seg) << 3) | reg_code(src_base));
                 } else {
-                    panic!("Sreg exige a registry.");
+                    return Err(ThotError::syntax("Sreg exige a registry."));
                 }
             }
             RegKind::General(dest_base) => {
+                if let Expression::Register(src) = value
+                    && src == destination
+                {
+                    self.warn(
+                        format!("henek %{destination}, %{src} has no effect"),
+                        "remove this instruction",
+                    );
+                }
                 if dest_spec.level <= Level::High {
                     // Ton code Henek existant pour ka, ib, ba...
                     self.emit_op32_prefix(code);
-                    ensure_supported_level("henek", destination, dest_spec.level);
+                    ensure_supported_level("henek", destination, dest_spec.level)?;
                     match value {
                         Expression::Number(n) => {
-                            ensure_number_fits("henek", destination, dest_spec.level, *n);
+                            ensure_number_fits("henek", destination, dest_spec.level, *n)?;
+                            code.push(0xB8 + reg_code(dest_base));
+                            code.extend_from_slice(&(*n as u32).to_le_bytes());
+                        }
+                        expr if expr.is_relocatable() => {
                             code.push(0xB8 + reg_code(dest_base));
-                            code.extend_from_slice(&n.to_le_bytes());
+                            self.record_absolute(code, expr);
                         }
                         Expression::Helix { ra, apophis } => {
                             ensure_helix_fits(
                                 "henek",
                                 destination,
                                 dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                                *ra,
+                                *apophis,
+                            )?;
                             // 1. Fusion des deux forces en un seul bloc de 32 bits
                             let n = ((*ra as i32) << 16) | (*apophis as i32);
                             code.push(0xB8 + reg_code(dest_base));
                             code.extend_from_slice(&n.to_le_bytes());
                         }
                         Expression::Register(src_name) => {
-                            let src_spec = parse_general_register(src_name);
+                            let src_spec = parse_general_register(src_name)?;
                             let src_base = match src_spec.kind {
                                 RegKind::General(base) => base,
                                 _ => unreachable!(),
@@ -1121,17 +2116,17 @@ impl Emitter {
                                 dest_spec.level,
                                 src_name,
                                 src_spec.level,
-                            );
-                            ensure_supported_level("henek", src_name, src_spec.level);
-                            code.push(0x8B);
-                            code.push(modrm_reg_reg(dest_base, src_base));
+                            )?;
+                            ensure_supported_level("henek", src_name, src_spec.level)?;
+                            code.push(0x8B); // MOV r32, r/m32 : la destination est le champ reg
+                            code.push(modrm_mov_reg_rm(dest_base, src_base));
                         }
                         _ => { /* ... identifiant ... */ }
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match value {
                         Expression::Register(src_name) => {
-                            let src_spec = parse_general_register(src_name);
+                            let src_spec = parse_general_register(src_name)?;
                             let src_base = match src_spec.kind {
                                 RegKind::General(base) => base,
                                 _ => unreachable!(),
@@ -1142,22 +2137,30 @@ impl Emitter {
                                 dest_spec.level,
                                 src_name,
                                 src_spec.level,
-                            );
+                            )?;
                             self.emit_mov_reg_reg(code, dest_base, src_base);
                         }
 
                         Expression::Helix { ra, apophis } => {
-                            let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                            let addr = self.alloc_helix_literal(
+                                "henek",
+                                destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
                             self.emit_mov_reg_imm32(code, dest_base, addr as u32);
                         }
-                        _ => panic!(
-                            "Henek only supports Helix literals or registers for 128-bit registers."
-                        ),
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Henek only supports Helix literals or registers for 128-bit registers.",
+                            ));
+                        }
                     }
                 } else if dest_spec.level == Level::Zenith {
                     match value {
                         Expression::Register(src_name) => {
-                            let src_spec = parse_general_register(src_name);
+                            let src_spec = parse_general_register(src_name)?;
                             let src_base = match src_spec.kind {
                                 RegKind::General(base) => base,
                                 _ => unreachable!(),
@@ -1168,52 +2171,66 @@ impl Emitter {
                                 dest_spec.level,
                                 src_name,
                                 src_spec.level,
-                            );
+                            )?;
                             self.emit_mov_reg_reg(code, dest_base, src_base);
                         }
                         Expression::Helix { ra, apophis } => {
-                            let addr = self.alloc_zenith_literal(*ra, *apophis);
+                            let addr = self.alloc_helix_literal(
+                                "henek",
+                                destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
                             self.emit_mov_reg_imm32(code, dest_base, addr as u32);
                         }
-                        _ => panic!(
-                            "Henek only supports Helix literals or registers for 256-bit registers."
-                        ),
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Henek only supports Helix literals or registers for 256-bit registers.",
+                            ));
+                        }
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Henek does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
             }
         }
+        Ok(())
     }
 
-    pub fn sema(&mut self, code_actual: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_general_register(destination);
+    pub fn sema(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(code_actual); // Stabilisation 32 bits
-            ensure_supported_level("sema", destination, dest_spec.level);
+            ensure_supported_level("sema", destination, dest_spec.level)?;
             match value {
                 Expression::Number(n) => {
                     // MODE 1 : Additionner un Nombre (ADD r/m32, imm32)
                     code_actual.push(0x81); // Opcode ADD
                     code_actual.push(modrm_imm(dest_base, 0));
-                    ensure_number_fits("sema", destination, dest_spec.level, *n);
-                    code_actual.extend_from_slice(&n.to_le_bytes());
+                    ensure_number_fits("sema", destination, dest_spec.level, *n)?;
+                    code_actual.extend_from_slice(&(*n as u32).to_le_bytes());
+                }
+                expr if expr.is_relocatable() => {
+                    // MODE 1 bis : Additionner une adresse résolue après la mise en page
+                    code_actual.push(0x81); // Opcode ADD
+                    code_actual.push(modrm_imm(dest_base, 0));
+                    self.record_absolute(code_actual, expr);
                 }
                 Expression::Helix { ra, apophis } => {
-                    ensure_helix_fits(
-                        "sema",
-                        destination,
-                        dest_spec.level,
-                        *ra as u128,
-                        *apophis as u128,
-                    );
+                    ensure_helix_fits("sema", destination, dest_spec.level, *ra, *apophis)?;
                     let n = ((*ra as i32) << 16) | (*apophis as i32);
                     code_actual.push(0x81); // Opcode ADD
                     code_actual.push(modrm_imm(dest_base, 0));
@@ -1223,29 +2240,33 @@ impl Emitter {
                     // MODE 2 : Additionner un Registre (ADD r/m32, reg32)
                     code_actual.push(0x01); // Opcode ADD registre à registre
 
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level);
-                    ensure_supported_level("sema", src, src_spec.level);
+                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level)?;
+                    ensure_supported_level("sema", src, src_spec.level)?;
 
                     // Formule x86 magique (ModR/M) : 0xC0 (11000000 en binaire) + (source * 8) + destination
                     let modrm = modrm_reg_reg(dest_base, src_base);
                     code_actual.push(modrm);
                 }
-                _ => panic!("Sema ne supporte que les nombres ou les registres."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Sema ne supporte que les nombres ou les registres.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Extreme {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(code_actual, RegBase::Si, src_base);
                     self.emit_rel16_prefix(code_actual);
@@ -1254,7 +2275,13 @@ impl Emitter {
                     self.record_jump(code_actual, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "sema",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(code_actual, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(code_actual);
@@ -1262,17 +2289,21 @@ impl Emitter {
                     let target = Expression::Identifier("__helix_add128".to_string());
                     self.record_jump(code_actual, &target);
                 }
-                _ => panic!("Sema only supports Helix literals or registers for 128-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Sema only supports Helix literals or registers for 128-bit.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Zenith {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("sema", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(code_actual, RegBase::Si, src_base);
                     self.emit_rel16_prefix(code_actual);
@@ -1281,7 +2312,13 @@ impl Emitter {
                     self.record_jump(code_actual, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_zenith_literal(*ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "sema",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(code_actual, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(code_actual);
@@ -1289,71 +2326,85 @@ impl Emitter {
                     let target = Expression::Identifier("__zenith_add256".to_string());
                     self.record_jump(code_actual, &target);
                 }
-                _ => panic!("Sema only supports Helix literals or registers for 256-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Sema only supports Helix literals or registers for 256-bit.",
+                    ));
+                }
             }
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Sema does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
         }
+        Ok(())
     }
 
-    pub fn shesa(&mut self, code_actual: &mut Vec<u8>, destination: &str, value: &Expression) {
-        let dest_spec = parse_general_register(destination);
+    pub fn shesa(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        destination: &str,
+        value: &Expression,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
         let dest_base = match dest_spec.kind {
             RegKind::General(base) => base,
             _ => unreachable!(),
         };
         if dest_spec.level <= Level::High {
             self.emit_op32_prefix(code_actual); // Stabilisation 32 bits
-            ensure_supported_level("shesa", destination, dest_spec.level);
+            ensure_supported_level("shesa", destination, dest_spec.level)?;
             match value {
                 Expression::Number(n) => {
                     let modrm = 0xC0 | (reg_code(dest_base) << 3) | reg_code(dest_base);
-                    ensure_number_fits("shesa", destination, dest_spec.level, *n);
+                    ensure_number_fits("shesa", destination, dest_spec.level, *n)?;
                     code_actual.push(0x69); // IMUL r32, r/m32, imm32
                     code_actual.push(modrm);
-                    code_actual.extend_from_slice(&n.to_le_bytes());
+                    code_actual.extend_from_slice(&(*n as u32).to_le_bytes());
+                }
+                expr if expr.is_relocatable() => {
+                    let modrm = 0xC0 | (reg_code(dest_base) << 3) | reg_code(dest_base);
+                    code_actual.push(0x69); // IMUL r32, r/m32, imm32
+                    code_actual.push(modrm);
+                    self.record_absolute(code_actual, expr);
                 }
                 Expression::Helix { ra, apophis } => {
                     let modrm = 0xC0 | (reg_code(dest_base) << 3) | reg_code(dest_base);
-                    ensure_helix_fits(
-                        "shesa",
-                        destination,
-                        dest_spec.level,
-                        *ra as u128,
-                        *apophis as u128,
-                    );
+                    ensure_helix_fits("shesa", destination, dest_spec.level, *ra, *apophis)?;
                     let n = ((*ra as i32) << 16) | (*apophis as i32);
                     code_actual.push(0x69);
                     code_actual.push(modrm);
                     code_actual.extend_from_slice(&n.to_le_bytes());
                 }
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("shesa", destination, dest_spec.level, src, src_spec.level);
-                    ensure_supported_level("shesa", src, src_spec.level);
+                    ensure_same_level("shesa", destination, dest_spec.level, src, src_spec.level)?;
+                    ensure_supported_level("shesa", src, src_spec.level)?;
                     let modrm = 0xC0 | (reg_code(dest_base) << 3) | reg_code(src_base);
                     code_actual.push(0x0F); // IMUL r32, r/m32
                     code_actual.push(0xAF);
                     code_actual.push(modrm);
                 }
-                _ => panic!("Shesa only supports numbers, Helix literals, or registers."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Shesa only supports numbers, Helix literals, or registers.",
+                    ));
+                }
             }
         } else if dest_spec.level == Level::Extreme {
             match value {
                 Expression::Register(src) => {
-                    let src_spec = parse_general_register(src);
+                    let src_spec = parse_general_register(src)?;
                     let src_base = match src_spec.kind {
                         RegKind::General(base) => base,
                         _ => unreachable!(),
                     };
-                    ensure_same_level("shesa", destination, dest_spec.level, src, src_spec.level);
+                    ensure_same_level("shesa", destination, dest_spec.level, src, src_spec.level)?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_reg(code_actual, RegBase::Si, src_base);
                     self.emit_rel16_prefix(code_actual);
@@ -1362,7 +2413,13 @@ impl Emitter {
                     self.record_jump(code_actual, &target);
                 }
                 Expression::Helix { ra, apophis } => {
-                    let addr = self.alloc_helix_literal(dest_spec.level, *ra, *apophis);
+                    let addr = self.alloc_helix_literal(
+                        "shesa",
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
                     self.emit_mov_reg_reg(code_actual, RegBase::Di, dest_base);
                     self.emit_mov_reg_imm32(code_actual, RegBase::Si, addr as u32);
                     self.emit_rel16_prefix(code_actual);
@@ -1370,18 +2427,529 @@ impl Emitter {
                     let target = Expression::Identifier("__helix_mul128".to_string());
                     self.record_jump(code_actual, &target);
                 }
-                _ => panic!("Shesa only supports Helix literals or registers for 128-bit."),
+                _ => {
+                    return Err(ThotError::syntax(
+                        "Shesa only supports Helix literals or registers for 128-bit.",
+                    ));
+                }
             }
         } else {
-            panic!(
+            return Err(ThotError::size_mismatch(format!(
                 "Shesa does not yet support registers beyond Extreme: %{} ({})",
                 destination, dest_spec.level
-            );
+            )));
+        }
+        Ok(())
+    }
+
+    // wep (DIV) et wep_ankh (IDIV) : EDX:EAX divisé, le quotient dans EAX, le reste dans EDX.
+    // Un diviseur registre nul sature comme les Helix au lieu de lever #DE :
+    // le quotient est plein et le reste garde le dividende.
+    fn diviser(
+        &mut self,
+        code_actual: &mut Vec<u8>,
+        verbe: &str,
+        destination: &str,
+        value: &Expression,
+        signee: bool,
+    ) -> ThotResult<()> {
+        let dest_spec = parse_general_register(destination)?;
+        ensure_division(verbe, destination, dest_spec.level, value)?;
+        let extension = if signee { 7 } else { 6 }; // IDIV ; DIV
+        if dest_spec.level <= Level::High {
+            let level = dest_spec.level;
+            let octet = level == Level::Base;
+            let diviseur = match value {
+                Expression::Register(src) => {
+                    let src_base = match parse_general_register(src)?.kind {
+                        RegKind::General(base) => base,
+                        _ => unreachable!(),
+                    };
+                    ensure_byte_register(verbe, src, level, src_base)?;
+                    src_base
+                }
+                // x86 ne divise pas par un immédiat : le diviseur passe par ECX
+                _ => RegBase::Ib,
+            };
+            // Le dividende est %ka étendu au double de sa largeur : AX pour un octet,
+            // DX:AX ou EDX:EAX au-delà. Le quotient revient dans %ka, le reste dans %da.
+            let mut division = Vec::new();
+            match (octet, signee) {
+                (true, false) => division.extend_from_slice(&[0x30, 0xE4]), // XOR AH, AH
+                (true, true) => {
+                    self.emit_operand_size(&mut division, Level::Medium);
+                    division.push(0x98); // CBW
+                }
+                (false, false) => {
+                    self.emit_operand_size(&mut division, level);
+                    division.extend_from_slice(&[0x31, 0xD2]); // XOR EDX, EDX
+                }
+                (false, true) => {
+                    self.emit_operand_size(&mut division, level);
+                    division.push(0x99); // CWD / CDQ
+                }
+            }
+            self.emit_operand_size(&mut division, level);
+            division.push(if octet { 0xF6 } else { 0xF7 });
+            division.push(modrm_imm(diviseur, extension));
+            if octet {
+                division.extend_from_slice(&[0x88, 0xE2]); // MOV DL, AH : le reste
+            }
+            match value {
+                Expression::Register(_) => {
+                    // Un diviseur nul sature : %da garde le dividende, %ka prend tous ses bits
+                    let mut saturation = Vec::new();
+                    self.emit_operand_size(&mut saturation, level);
+                    if octet {
+                        saturation.extend_from_slice(&[0x88, 0xC2]); // MOV DL, AL
+                        saturation.extend_from_slice(&[0xB0, 0xFF]); // MOV AL, 0xFF
+                    } else {
+                        saturation.extend_from_slice(&[0x89, 0xC2]); // MOV EDX, EAX
+                        self.emit_operand_size(&mut saturation, level);
+                        saturation.extend_from_slice(&[0x83, 0xC8, 0xFF]); // OR EAX, -1
+                    }
+                    saturation.extend_from_slice(&[0xEB, division.len() as u8]); // JMP fin
+
+                    self.emit_operand_size(code_actual, level);
+                    code_actual.push(if octet { 0x84 } else { 0x85 }); // TEST src, src
+                    code_actual.push(modrm_reg_reg(diviseur, diviseur));
+                    code_actual.extend_from_slice(&[0x75, saturation.len() as u8]); // JNZ division
+                    code_actual.extend_from_slice(&saturation);
+                    code_actual.extend_from_slice(&division);
+                }
+                _ => {
+                    self.emit_op32_prefix(code_actual);
+                    code_actual.push(0x51); // PUSH ECX
+                    match value {
+                        Expression::Number(n) => {
+                            self.emit_mov_reg_imm32(code_actual, RegBase::Ib, *n as u32);
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            let n = (*ra << (level.bits() / 2)) | *apophis;
+                            self.emit_mov_reg_imm32(code_actual, RegBase::Ib, n as u32);
+                        }
+                        expr if expr.is_relocatable() => {
+                            self.emit_op32_prefix(code_actual);
+                            code_actual.push(0xB9); // MOV ECX, imm32
+                            self.record_absolute(code_actual, expr);
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(format!(
+                                "'{verbe}' only supports numbers, Helix literals, or registers."
+                            )));
+                        }
+                    }
+                    code_actual.extend_from_slice(&division);
+                    self.emit_op32_prefix(code_actual);
+                    code_actual.push(0x59); // POP ECX
+                }
+            }
+        } else {
+            // EDX pointe déjà le bloc du reste (%eda, %xda)
+            self.emit_mov_reg_reg(code_actual, RegBase::Di, RegBase::Ka);
+            match value {
+                Expression::Register(src) => {
+                    let src_base = match parse_general_register(src)?.kind {
+                        RegKind::General(base) => base,
+                        _ => unreachable!(),
+                    };
+                    self.emit_mov_reg_reg(code_actual, RegBase::Si, src_base);
+                }
+                Expression::Helix { ra, apophis } => {
+                    let addr = self.alloc_helix_literal(
+                        verbe,
+                        destination,
+                        dest_spec.level,
+                        *ra,
+                        *apophis,
+                    )?;
+                    self.emit_mov_reg_imm32(code_actual, RegBase::Si, addr as u32);
+                }
+                _ => unreachable!("ensure_division only lets registers and Helix through"),
+            }
+            let operation = if signee { "idiv" } else { "div" };
+            self.emit_call_routine(code_actual, helix_routine(operation, dest_spec.level));
+        }
+        Ok(())
+    }
+
+    // Traduit une instruction (hors étiquettes, placées par generer_binaire) en code machine
+    fn emit_instruction(
+        &mut self,
+        actual_code: &mut Vec<u8>,
+        instruction: Instruction,
+    ) -> ThotResult<()> {
+        match instruction {
+            Instruction::Neheh { target } => {
+                self.neheh(actual_code, &target)?;
+            }
+            Instruction::Jena { target } => {
+                self.jena(actual_code, &target)?;
+            }
+            Instruction::Henek { destination, value } => {
+                self.henek(actual_code, &destination, &value)?;
+            }
+            // 2. Le NAMA est déjà rangé dans le Noun par la table des symboles
+            Instruction::Nama { .. } => {}
+            Instruction::Push { target } => {
+                self.push(actual_code, &target)?;
+            }
+            Instruction::Pop { destination } => {
+                self.pop(actual_code, &destination)?;
+            }
+            Instruction::In { port } => {
+                self.io_in(actual_code, &port)?;
+            }
+            Instruction::Out { port } => {
+                self.io_out(actual_code, &port)?;
+            }
+            Instruction::Her { target } => {
+                self.her(actual_code, &target)?;
+            }
+            Instruction::Kher { target } => {
+                self.kher(actual_code, &target)?;
+            }
+            Instruction::Isfet { target } => {
+                self.isfet(actual_code, &target)?;
+            }
+            Instruction::Kheper { source, adresse } => {
+                self.kheper(actual_code, &source, &adresse)?;
+            }
+            Instruction::Rdtsc => {
+                actual_code.push(0x0F);
+                actual_code.push(0x31);
+            }
+            Instruction::Nehes { vector, handler } => self.nehes(&vector, handler)?,
+            Instruction::Nis { vector } => {
+                actual_code.push(0xCD); // INT imm8
+                actual_code.push(Self::vecteur("nis", &vector)?);
+            }
+            Instruction::Iwi => actual_code.push(0xCF), // IRET (IRETD en mode protégé)
+            Instruction::Khetem => actual_code.push(0xFA), // CLI
+            Instruction::Wen => actual_code.push(0xFB), // STI
+            Instruction::Hotep => actual_code.push(0xF4), // HLT
+            // Traduction de : sema %registre, valeur (ADD)
+            Instruction::Sema { destination, value } => {
+                self.sema(actual_code, &destination, &value)?;
+            }
+            // Traduction de : shesa %registre, valeur (MUL)
+            Instruction::Shesa { destination, value } => {
+                self.shesa(actual_code, &destination, &value)?;
+            }
+            Instruction::Wep { destination, value } => {
+                self.diviser(actual_code, "wep", &destination, &value, false)?;
+            }
+            Instruction::WepAnkh { destination, value } => {
+                self.diviser(actual_code, "wep_ankh", &destination, &value, true)?;
+            }
+            Instruction::Kherp => {
+                self.kherp(actual_code)?;
+            }
+            // Traduction de : sena %registre, adresse (MOV reg, [mem])
+            Instruction::Sena {
+                destination,
+                adresse,
+            } => {
+                self.sena(actual_code, &destination, &adresse)?;
+            }
+            Instruction::Sedjem { destination } => {
+                self.setjem(actual_code, &destination)?;
+            }
+            Instruction::Henet { destination, value } => {
+                self.henet(actual_code, &destination, &value)?;
+            }
+            Instruction::Mer { destination, value } => {
+                self.mer(actual_code, &destination, &value)?;
+            }
+            Instruction::Pesh { destination, value } => {
+                self.pesh(actual_code, &destination, &value)?;
+            }
+            Instruction::Maa { destination, value } => {
+                self.maa(actual_code, &destination, &value)?;
+            }
+            Instruction::Kem { destination } => self.kem(actual_code, &destination)?,
+            Instruction::Hem { destination } => self.hem(actual_code, &destination)?,
+            Instruction::Wer { destination, count } => {
+                self.decaler(actual_code, Decalage::Wer, &destination, &count)?;
+            }
+            Instruction::Nedjes { destination, count } => {
+                self.decaler(actual_code, Decalage::Nedjes, &destination, &count)?;
+            }
+            Instruction::NedjesAnkh { destination, count } => {
+                self.decaler(actual_code, Decalage::NedjesAnkh, &destination, &count)?;
+            }
+            Instruction::Deben { destination, count } => {
+                self.decaler(actual_code, Decalage::Deben, &destination, &count)?;
+            }
+            Instruction::Nebed { destination, count } => {
+                self.decaler(actual_code, Decalage::Nebed, &destination, &count)?;
+            }
+            Instruction::Return { resultat } => {
+                match resultat {
+                    Expression::Number(n) => {
+                        // MOV EAX, n (Opcode 0xB8)
+                        self.emit_op32_prefix(actual_code); // LA PROTECTION V4
+                        actual_code.push(0xB8);
+                        actual_code.extend_from_slice(&imm32("return", n)?.to_le_bytes());
+                    }
+                    Expression::Register(r) => {
+                        let reg_spec = parse_general_register(&r)?;
+                        ensure_supported_level("return", &r, reg_spec.level)?;
+                        if !matches!(reg_spec.kind, RegKind::General(RegBase::Ka))
+                            || reg_spec.level != Level::Base
+                        {
+                            return Err(ThotError::syntax(
+                                "Pour l'instant, le Scribe ne sait renvoyer que des nombres purs ou %ka.",
+                            ));
+                        }
+                    }
+                    _ => {
+                        return Err(ThotError::syntax(
+                            "Le Return de Maât ne gère que les nombres pour le moment.",
+                        ));
+                    }
+                }
+                // Note : Pour l'instant, on ignore 'resultat'. Dans le futur,
+                // on pourra placer 'resultat' dans %ka juste avant de partir !
+
+                // Le processeur lit la Pile, retrouve son chemin, et reprend son exécution.
+                // OpCode pour RET (Return) : 0xC3
+                actual_code.push(0xC3);
+            }
+            Instruction::Wab => {
+                self.wab(actual_code)?;
+            }
+            Instruction::Per { message } => {
+                self.per(actual_code, &message)?;
+            }
+            Instruction::Wdj { left, right } => {
+                let left_spec = parse_general_register(&left)?;
+                let left_base = match left_spec.kind {
+                    RegKind::General(base) => base,
+                    _ => unreachable!(),
+                };
+                if left_spec.level <= Level::High {
+                    // 1. On force le mode 32 bits pour la précision
+                    self.emit_op32_prefix(actual_code);
+                    ensure_supported_level("wdj", &left, left_spec.level)?;
+                    // 2. OpCode universel de comparaison : 0x81
+                    match right {
+                        Expression::Number(n) => {
+                            // MODE 1 : Comparer à un Nombre (CMP r/m32, imm32)
+                            actual_code.push(0x81); // OpCode universel avec nombre
+                            actual_code.push(modrm_imm(left_base, 7));
+                            ensure_number_fits("wdj", &left, left_spec.level, n)?;
+                            actual_code.extend_from_slice(&(n as u32).to_le_bytes());
+                        }
+                        expr if expr.is_relocatable() => {
+                            // MODE 1 bis : Comparer à une adresse résolue après la mise en page
+                            actual_code.push(0x81); // OpCode universel avec nombre
+                            actual_code.push(modrm_imm(left_base, 7));
+                            self.record_absolute(actual_code, &expr);
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            let n = ((ra as i32) << 16) | (apophis as i32);
+                            ensure_helix_fits("wdj", &left, left_spec.level, ra, apophis)?;
+                            actual_code.push(0x81);
+                            actual_code.push(modrm_imm(left_base, 7));
+                            actual_code.extend_from_slice(&n.to_le_bytes());
+                        }
+                        Expression::Register(right_reg) => {
+                            // MODE 2 : Comparer à un autre Registre (CMP r/m32, reg32)
+                            actual_code.push(0x39); // OpCode pour CMP registre à registre
+
+                            let right_spec = parse_general_register(&right_reg)?;
+                            let right_base = match right_spec.kind {
+                                RegKind::General(base) => base,
+                                _ => unreachable!(),
+                            };
+                            ensure_same_level(
+                                "wdj",
+                                &left,
+                                left_spec.level,
+                                &right_reg,
+                                right_spec.level,
+                            )?;
+                            ensure_supported_level("wdj", &right_reg, right_spec.level)?;
+
+                            // Formule x86 magique (ModR/M) : 0xC0 (11000000) + (source * 8) + destination
+                            let modrm = modrm_reg_reg(left_base, right_base);
+                            actual_code.push(modrm);
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "La Balance ne sait peser que des nombres ou des registres.",
+                            ));
+                        }
+                    }
+                } else if left_spec.level == Level::Extreme {
+                    match right {
+                        Expression::Register(right_reg) => {
+                            let right_spec = parse_general_register(&right_reg)?;
+                            let right_base = match right_spec.kind {
+                                RegKind::General(base) => base,
+                                _ => unreachable!(),
+                            };
+                            ensure_same_level(
+                                "wdj",
+                                &left,
+                                left_spec.level,
+                                &right_reg,
+                                right_spec.level,
+                            )?;
+                            self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
+                            self.emit_mov_reg_reg(actual_code, RegBase::Si, right_base);
+                            self.emit_rel16_prefix(actual_code);
+                            actual_code.push(0xE8);
+                            let target = Expression::Identifier("__helix_cmp128".to_string());
+                            self.record_jump(actual_code, &target);
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            let addr = self.alloc_helix_literal(
+                                "wdj",
+                                &left,
+                                left_spec.level,
+                                ra,
+                                apophis,
+                            )?;
+                            self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
+                            self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
+                            self.emit_rel16_prefix(actual_code);
+                            actual_code.push(0xE8);
+                            let target = Expression::Identifier("__helix_cmp128".to_string());
+                            self.record_jump(actual_code, &target);
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Wdj only supports Helix literals or registers for 128-bit.",
+                            ));
+                        }
+                    }
+                } else if left_spec.level == Level::Zenith {
+                    match right {
+                        Expression::Register(right_reg) => {
+                            let right_spec = parse_general_register(&right_reg)?;
+                            let right_base = match right_spec.kind {
+                                RegKind::General(base) => base,
+                                _ => unreachable!(),
+                            };
+                            ensure_same_level(
+                                "wdj",
+                                &left,
+                                left_spec.level,
+                                &right_reg,
+                                right_spec.level,
+                            )?;
+                            self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
+                            self.emit_mov_reg_reg(actual_code, RegBase::Si, right_base);
+                            self.emit_rel16_prefix(actual_code);
+                            actual_code.push(0xE8);
+                            let target = Expression::Identifier("__zenith_cmp256".to_string());
+                            self.record_jump(actual_code, &target);
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            let addr = self.alloc_helix_literal(
+                                "wdj",
+                                &left,
+                                left_spec.level,
+                                ra,
+                                apophis,
+                            )?;
+                            self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
+                            self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
+                            self.emit_rel16_prefix(actual_code);
+                            actual_code.push(0xE8);
+                            let target = Expression::Identifier("__zenith_cmp256".to_string());
+                            self.record_jump(actual_code, &target);
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Wdj only supports Helix literals or registers for 256-bit.",
+                            ));
+                        }
+                    }
+                } else {
+                    return Err(ThotError::size_mismatch(format!(
+                        "Wdj does not yet support registers beyond Extreme: %{} ({})",
+                        left, left_spec.level
+                    )));
+                }
+            }
+            // Traduction de : ankh target (Saut Conditionnel : JE)
+            Instruction::Ankh { target } => {
+                self.ankh(actual_code, &target)?;
+            }
+            Instruction::Duat { phrase, address } => {
+                self.duat(actual_code, &phrase, &address)?;
+            }
+            // Traduction de : kheb %registre, valeur (SUB)
+            Instruction::Kheb { destination, value } => {
+                self.kheb(actual_code, &destination, &value)?;
+            }
+            Instruction::HerAnkh { target } => {
+                self.herankh(actual_code, &target)?;
+            }
+            Instruction::KherAnkh { target } => {
+                self.kherankh(actual_code, &target)?;
+            }
+            Instruction::Dema { path, .. } => {
+                return Err(ThotError::layout(format!(
+                    "Fatal error by Maat: The Transmitter found a 'dema' instruction pointing to '{path}'. The Tisserand forgot to merge this tablet before generating the binary!"
+                )));
+            }
+            Instruction::Sesh { level, values } => {
+                self.sesh(actual_code, level, &values)?;
+            }
+            Instruction::Djed { alignment } => {
+                self.djed(actual_code, &alignment)?;
+            }
+            Instruction::Shu { size } => {
+                let fin = actual_code.len() + count("shu", &size)?;
+                actual_code.resize(fin, 0x00);
+            }
+            Instruction::Heh { count, instruction } => {
+                self.heh(actual_code, &count, &instruction)?;
+            }
+            Instruction::Octets(octets) => actual_code.extend_from_slice(&octets),
+            Instruction::Ini { path } => {
+                return Err(ThotError::layout(format!(
+                    "Fatal error by Maat: The Transmitter found an 'ini' instruction pointing to '{path}'. The Tisserand forgot to read this file before generating the binary!"
+                )));
+            }
+            Instruction::Heka { nom, .. } | Instruction::Invocation { nom, .. } => {
+                return Err(ThotError::layout(format!(
+                    "Fatal error by Maat: The Transmitter found the heka '{nom}' still folded. It must be expanded before generating the binary!"
+                )));
+            }
+            // export/private ne restent que dans la tablette principale : personne ne l'importe
+            Instruction::Smen { .. } | Instruction::Export(_) | Instruction::Private(_) => {}
+            Instruction::CurrentAddress => {}
+            Instruction::Dja { segment, target } => self.dja(actual_code, segment, &target)?,
+            Instruction::Sokh { destination } => self.sokh(actual_code, &destination)?,
+            Instruction::Wah { destination } => self.wah(actual_code, &destination)?,
+            Instruction::Tut { level } => self.emit_bloc(actual_code, level, 0xA4), // MOVS
+            Instruction::Meh { level } => self.emit_bloc(actual_code, level, 0xAA), // STOS
+            Instruction::Sia { level } => self.emit_bloc(actual_code, level, 0xA6), // CMPS
+            Instruction::Hery { target } => self.saut_si(actual_code, 0x87, &target)?, // JA
+            Instruction::Khery { target } => self.saut_si(actual_code, 0x82, &target)?, // JB
+            Instruction::HeryAnkh { target } => self.saut_si(actual_code, 0x83, &target)?, // JAE
+            Instruction::KheryAnkh { target } => self.saut_si(actual_code, 0x86, &target)?, // JBE
+            Instruction::Fai { target } => self.saut_si(actual_code, 0x82, &target)?, // JC
+            Instruction::NenFai { target } => self.saut_si(actual_code, 0x83, &target)?, // JNC
+            Instruction::Bah { target } => self.saut_si(actual_code, 0x80, &target)?, // JO
+            Instruction::NenBah { target } => self.saut_si(actual_code, 0x81, &target)?, // JNO
+            Instruction::Tep { target } => self.saut_si(actual_code, 0x88, &target)?, // JS
+            Instruction::NenTep { target } => self.saut_si(actual_code, 0x89, &target)?, // JNS
+            Instruction::Mety { target } => self.saut_si(actual_code, 0x8A, &target)?, // JP
+            Instruction::NenMety { target } => self.saut_si(actual_code, 0x8B, &target)?, // JNP
+            Instruction::Renpet { target } => self.renpet(actual_code, &target)?,
+            Instruction::Label(_) => {}
         }
+        Ok(())
     }
 
     // Le grand convertisseur: AST -> Code Machine (Binaire)
-    pub fn generer_binaire(&mut self, is_bootloader: bool) -> Vec<u8> {
+    pub fn generer_binaire(&mut self, is_bootloader: bool) -> ThotResult<Vec<u8>> {
         let mut stage1_code: Vec<u8> = Vec::new();
         let mut stage2_code: Vec<u8> = Vec::new();
         let mut dans_noyau = false; // Le basculement vers l'infini
@@ -1406,7 +2974,21 @@ impl Emitter {
         }
         let base_stage2 = STAGE_TWO;
         let instructions = self.instructions.clone();
-        for instruction in instructions {
+        // Table des symboles : chaque variable reçoit son adresse dans le Noun avant
+        // l'encodage, pour pouvoir être utilisée avant sa déclaration.
+        for Spanned { node, span } in &instructions {
+            if let Instruction::Nama { name, value } = node {
+                self.current_span = *span;
+                // Unifie le NAMA avec BLAKE3 (SLS Pur)
+                self.nama(name, value).map_err(|e| e.at(*span))?;
+            }
+        }
+        for Spanned {
+            node: instruction,
+            span,
+        } in instructions
+        {
+            self.current_span = span;
             if let Instruction::Label(ref nom) = instruction
                 && (nom == "kernel" || nom == "noyau")
             {
@@ -1419,109 +3001,8 @@ impl Emitter {
                 &mut stage1_code
             };
             let base_actuelle = if dans_noyau { base_stage2 } else { base_stage1 };
+            self.section_base = base_actuelle;
             match instruction {
-                Instruction::Neheh { target } => {
-                    self.neheh(actual_code, &target);
-                }
-                Instruction::Jena { target } => {
-                    self.jena(actual_code, &target);
-                }
-                Instruction::Henek { destination, value } => {
-                    self.henek(actual_code, &destination, &value);
-                }
-                // 2. Unifie le NAMA avec BLAKE3 (SLS Pur)
-                Instruction::Nama { name, value } => {
-                    self.nama(&name, &value);
-                }
-                Instruction::Push { target } => {
-                    self.push(actual_code, &target);
-                }
-                Instruction::Pop { destination } => {
-                    self.pop(actual_code, &destination);
-                }
-                Instruction::In { port } => {
-                    self.io_in(actual_code, &port);
-                }
-                Instruction::Out { port } => {
-                    self.io_out(actual_code, &port);
-                }
-                Instruction::Her { target } => {
-                    self.her(actual_code, &target);
-                }
-                Instruction::Kher { target } => {
-                    self.kher(actual_code, &target);
-                }
-                Instruction::Isfet { target } => {
-                    self.isfet(actual_code, &target);
-                }
-                Instruction::Kheper { source, adresse } => {
-                    self.kheper(actual_code, &source, &adresse);
-                }
-                Instruction::Rdtsc => {
-                    actual_code.push(0x0F);
-                    actual_code.push(0x31);
-                }
-                // Traduction de : sema %registre, valeur (ADD)
-                Instruction::Sema { destination, value } => {
-                    self.sema(actual_code, &destination, &value);
-                }
-                // Traduction de : shesa %registre, valeur (MUL)
-                Instruction::Shesa { destination, value } => {
-                    self.shesa(actual_code, &destination, &value);
-                }
-                Instruction::Kherp => {
-                    self.kherp(actual_code);
-                }
-                // Traduction de : sena %registre, adresse (MOV reg, [mem])
-                Instruction::Sena {
-                    destination,
-                    adresse,
-                } => {
-                    self.sena(actual_code, &destination, &adresse);
-                }
-                Instruction::Sedjem { destination } => {
-                    self.setjem(actual_code, &destination);
-                }
-                Instruction::Henet { destination, value } => {
-                    self.henet(actual_code, &destination, &value);
-                }
-                Instruction::Mer { destination, value } => {
-                    self.mer(actual_code, &destination, &value);
-                }
-                Instruction::Return { resultat } => {
-                    match resultat {
-                        Expression::Number(n) => {
-                            // MOV EAX, n (Opcode 0xB8)
-                            self.emit_op32_prefix(actual_code); // LA PROTECTION V4
-                            actual_code.push(0xB8);
-                            actual_code.extend_from_slice(&n.to_le_bytes());
-                        }
-                        Expression::Register(r) => {
-                            let reg_spec = parse_general_register(&r);
-                            ensure_supported_level("return", &r, reg_spec.level);
-                            if !matches!(reg_spec.kind, RegKind::General(RegBase::Ka))
-                                || reg_spec.level != Level::Base
-                            {
-                                panic!(
-                                    "Pour l'instant, le Scribe ne sait renvoyer que des nombres purs ou %ka."
-                                );
-                            }
-                        }
-                        _ => panic!("Le Return de Maât ne gère que les nombres pour le moment."),
-                    }
-                    // Note : Pour l'instant, on ignore 'resultat'. Dans le futur,
-                    // on pourra placer 'resultat' dans %ka juste avant de partir !
-
-                    // Le processeur lit la Pile, retrouve son chemin, et reprend son exécution.
-                    // OpCode pour RET (Return) : 0xC3
-                    actual_code.push(0xC3);
-                }
-                Instruction::Wab => {
-                    self.wab(actual_code);
-                }
-                Instruction::Per { message } => {
-                    self.per(actual_code, &message);
-                }
                 Instruction::Label(nom) => {
                     // On utilise base_actuelle (0x7C00 ou 0x7E00) au lieu de base_addr !
                     self.labels
@@ -1538,169 +3019,24 @@ impl Emitter {
                         self.protected_mode_enabled = true;
                     }
                 }
-                Instruction::Wdj { left, right } => {
-                    let left_spec = parse_general_register(&left);
-                    let left_base = match left_spec.kind {
-                        RegKind::General(base) => base,
-                        _ => unreachable!(),
-                    };
-                    if left_spec.level <= Level::High {
-                        // 1. On force le mode 32 bits pour la précision
-                        self.emit_op32_prefix(actual_code);
-                        ensure_supported_level("wdj", &left, left_spec.level);
-                        // 2. OpCode universel de comparaison : 0x81
-                        match right {
-                            Expression::Number(n) => {
-                                // MODE 1 : Comparer à un Nombre (CMP r/m32, imm32)
-                                actual_code.push(0x81); // OpCode universel avec nombre
-                                actual_code.push(modrm_imm(left_base, 7));
-                                ensure_number_fits("wdj", &left, left_spec.level, n);
-                                actual_code.extend_from_slice(&n.to_le_bytes());
-                            }
-                            Expression::Helix { ra, apophis } => {
-                                let n = ((ra as i32) << 16) | (apophis as i32);
-                                ensure_helix_fits(
-                                    "wdj",
-                                    &left,
-                                    left_spec.level,
-                                    ra as u128,
-                                    apophis as u128,
-                                );
-                                actual_code.push(0x81);
-                                actual_code.push(modrm_imm(left_base, 7));
-                                actual_code.extend_from_slice(&n.to_le_bytes());
-                            }
-                            Expression::Register(right_reg) => {
-                                // MODE 2 : Comparer à un autre Registre (CMP r/m32, reg32)
-                                actual_code.push(0x39); // OpCode pour CMP registre à registre
-
-                                let right_spec = parse_general_register(&right_reg);
-                                let right_base = match right_spec.kind {
-                                    RegKind::General(base) => base,
-                                    _ => unreachable!(),
-                                };
-                                ensure_same_level(
-                                    "wdj",
-                                    &left,
-                                    left_spec.level,
-                                    &right_reg,
-                                    right_spec.level,
-                                );
-                                ensure_supported_level("wdj", &right_reg, right_spec.level);
-
-                                // Formule x86 magique (ModR/M) : 0xC0 (11000000) + (source * 8) + destination
-                                let modrm = modrm_reg_reg(left_base, right_base);
-                                actual_code.push(modrm);
-                            }
-                            _ => {
-                                panic!("La Balance ne sait peser que des nombres ou des registres.")
-                            }
-                        }
-                    } else if left_spec.level == Level::Extreme {
-                        match right {
-                            Expression::Register(right_reg) => {
-                                let right_spec = parse_general_register(&right_reg);
-                                let right_base = match right_spec.kind {
-                                    RegKind::General(base) => base,
-                                    _ => unreachable!(),
-                                };
-                                ensure_same_level(
-                                    "wdj",
-                                    &left,
-                                    left_spec.level,
-                                    &right_reg,
-                                    right_spec.level,
-                                );
-                                self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
-                                self.emit_mov_reg_reg(actual_code, RegBase::Si, right_base);
-                                self.emit_rel16_prefix(actual_code);
-                                actual_code.push(0xE8);
-                                let target = Expression::Identifier("__helix_cmp128".to_string());
-                                self.record_jump(actual_code, &target);
-                            }
-                            Expression::Helix { ra, apophis } => {
-                                let addr = self.alloc_helix_literal(left_spec.level, ra, apophis);
-                                self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
-                                self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
-                                self.emit_rel16_prefix(actual_code);
-                                actual_code.push(0xE8);
-                                let target = Expression::Identifier("__helix_cmp128".to_string());
-                                self.record_jump(actual_code, &target);
-                            }
-                            _ => {
-                                panic!("Wdj only supports Helix literals or registers for 128-bit.")
-                            }
-                        }
-                    } else if left_spec.level == Level::Zenith {
-                        match right {
-                            Expression::Register(right_reg) => {
-                                let right_spec = parse_general_register(&right_reg);
-                                let right_base = match right_spec.kind {
-                                    RegKind::General(base) => base,
-                                    _ => unreachable!(),
-                                };
-                                ensure_same_level(
-                                    "wdj",
-                                    &left,
-                                    left_spec.level,
-                                    &right_reg,
-                                    right_spec.level,
-                                );
-                                self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
-                                self.emit_mov_reg_reg(actual_code, RegBase::Si, right_base);
-                                self.emit_rel16_prefix(actual_code);
-                                actual_code.push(0xE8);
-                                let target = Expression::Identifier("__zenith_cmp256".to_string());
-                                self.record_jump(actual_code, &target);
-                            }
-                            Expression::Helix { ra, apophis } => {
-                                let addr = self.alloc_zenith_literal(ra, apophis);
-                                self.emit_mov_reg_reg(actual_code, RegBase::Di, left_base);
-                                self.emit_mov_reg_imm32(actual_code, RegBase::Si, addr as u32);
-                                self.emit_rel16_prefix(actual_code);
-                                actual_code.push(0xE8);
-                                let target = Expression::Identifier("__zenith_cmp256".to_string());
-                                self.record_jump(actual_code, &target);
-                            }
-                            _ => {
-                                panic!("Wdj only supports Helix literals or registers for 256-bit.")
-                            }
-                        }
-                    } else {
-                        panic!(
-                            "Wdj does not yet support registers beyond Extreme: %{} ({})",
-                            left, left_spec.level
-                        );
-                    }
+                autre => {
+                    self.instruction_start = actual_code.len();
+                    self.emit_instruction(actual_code, autre)
+                        .map_err(|e| e.at(span))?
                 }
-                // Traduction de : ankh target (Saut Conditionnel : JE)
-                Instruction::Ankh { target } => {
-                    self.ankh(actual_code, &target);
-                }
-                Instruction::Duat { phrase, address } => {
-                    self.duat(actual_code, &phrase, &address);
-                }
-                // Traduction de : kheb %registre, valeur (SUB)
-                Instruction::Kheb { destination, value } => {
-                    self.kheb(actual_code, &destination, &value);
-                }
-                Instruction::HerAnkh { target } => {
-                    self.herankh(actual_code, &target);
-                }
-                Instruction::KherAnkh { target } => {
-                    self.kherankh(actual_code, &target);
-                }
-                Instruction::Dema { path } => {
-                    panic!(
-                        "Fatal error by Maat: The Transmitter found a 'dema' instruction pointing to '{path}'. The Tisserand forgot to merge this tablet before generating the binary!",
-                    );
-                }
-                Instruction::Smen { .. } => {}
-                Instruction::CurrentAddress => {}
-                Instruction::Dja { segment, target } => self.dja(actual_code, segment, &target),
-                Instruction::Sokh { destination } => self.sokh(actual_code, &destination),
             }
-        } // Injection de la routine print dans le Stage 2 (pour ne pas saturer le Stage 1)
+        }
+        // Les routines Helix de la division, des opérations bit à bit et des décalages
+        // pèsent des centaines d'octets chacune : seules celles qu'un appel vise sont posées
+        let appelees: HashSet<String> = self
+            .jump
+            .iter()
+            .map(|patch| &patch.target)
+            .chain(self.gestionnaires.iter().map(|(_, handler, _)| handler))
+            .flat_map(identifiers)
+            .map(str::to_string)
+            .collect();
+        // Injection de la routine print dans le Stage 2 (pour ne pas saturer le Stage 1)
         // --- Injection UNIQUE de la routine print améliorée ---
         // --- Helix 128 Helpers (always available in Stage2) ---
         self.labels.insert(
@@ -1957,6 +3293,18 @@ impl Emitter {
         ];
         stage2_code.extend_from_slice(&helix_mul);
 
+        // La division longue de wep et wep_ankh, canal par canal
+        for level in [Level::Extreme, Level::Zenith] {
+            for (operation, signee) in [("div", false), ("idiv", true)] {
+                let nom = helix_routine(operation, level);
+                if appelees.contains(&nom) {
+                    self.labels
+                        .insert(nom, base_stage2 + (stage2_code.len() as isize));
+                    stage2_code.extend_from_slice(&routine_division(level.bytes() as u8, signee));
+                }
+            }
+        }
+
         self.labels.insert(
             "__helix_and128".to_string(),
             base_stage2 + (stage2_code.len() as isize),
@@ -1993,6 +3341,28 @@ impl Emitter {
         ];
         stage2_code.extend_from_slice(&helix_cmp);
 
+        // Les routines bit à bit de pesh, maa, kem, hem et des décalages, pour Extreme et Zenith
+        for level in [Level::Extreme, Level::Zenith] {
+            let octets = level.bytes() as u8;
+            let mut routines = vec![
+                ("xor", routine_xor(octets)),
+                ("test", routine_test(octets)),
+                ("not", routine_not(octets)),
+                ("neg", routine_neg(octets)),
+            ];
+            for decalage in Decalage::TOUS {
+                routines.push((decalage.routine(), routine_decalage(octets, decalage)));
+            }
+            for (operation, routine) in routines {
+                let nom = helix_routine(operation, level);
+                if appelees.contains(&nom) {
+                    self.labels
+                        .insert(nom, base_stage2 + (stage2_code.len() as isize));
+                    stage2_code.extend_from_slice(&routine);
+                }
+            }
+        }
+
         if is_bootloader {
             self.labels.insert(
                 "std_print".to_string(),
@@ -2069,18 +3439,33 @@ impl Emitter {
             ];
             stage2_code.extend_from_slice(&phoenix_rebirth);
 
-            // --- IDT (32 exceptions) ---
+            // --- IDT (32 exceptions, étendue jusqu'au plus haut vecteur lié par 'nehes') ---
+            // Chaque vecteur sans gestionnaire renaît par le Phénix.
             let idt_offset = stage2_code.len();
             let isr_addr = (base_stage2 + isr_offset as isize) as u32;
-            let isr_off16 = isr_addr as u16;
-            let isr_off_hi = (isr_addr >> 16) as u16;
+            let entrees = self
+                .gestionnaires
+                .iter()
+                .map(|(v, ..)| *v as usize + 1)
+                .fold(32, usize::max);
+            let mut cibles = vec![isr_addr; entrees];
+            let lookup = |name: &str| self.labels.get(name).map(|addr| *addr as i64);
+            for (v, handler, span) in &self.gestionnaires {
+                let adresse = evaluate(handler, 0, &lookup).map_err(|e| e.at(*span))?;
+                cibles[*v as usize] = u32::try_from(adresse).map_err(|_| {
+                    ThotError::layout(format!(
+                        "Handler of interrupt vector {v} is out of reach at {adresse}"
+                    ))
+                    .at(*span)
+                })?;
+            }
             let mut idt: Vec<u8> = Vec::new();
-            for _ in 0..32 {
-                idt.extend_from_slice(&isr_off16.to_le_bytes()); // offset low
+            for cible in cibles {
+                idt.extend_from_slice(&(cible as u16).to_le_bytes()); // offset low
                 idt.extend_from_slice(&0x08u16.to_le_bytes()); // code selector
                 idt.push(0x00); // zero
                 idt.push(0x8E); // present, ring0, 32-bit interrupt gate
-                idt.extend_from_slice(&isr_off_hi.to_le_bytes()); // offset high
+                idt.extend_from_slice(&((cible >> 16) as u16).to_le_bytes()); // offset high
             }
             stage2_code.extend_from_slice(&idt);
 
@@ -2111,6 +3496,29 @@ impl Emitter {
             stage2_code.extend_from_slice(&gdt_limit.to_le_bytes());
             stage2_code.extend_from_slice(&gdt_base.to_le_bytes());
 
+            // Chaque vecteur au-delà des 32 exceptions ajoute 8 octets à l'IDT. Si c'est elle
+            // qui pousse le Stage 2 dans le Noun, l'erreur pointe le 'nehes' qui l'a agrandie.
+            // Comme pour le binaire final, les tailles comptent les 512 octets du Stage 1.
+            let limite = NOUN_BASE as usize - base_stage1 as usize;
+            let taille = 512 + stage2_code.len();
+            let ajout = (entrees - 32) * 8;
+            if is_bootloader
+                && taille > limite
+                && taille - ajout <= limite
+                && let Some((vecteur, _, span)) = self.gestionnaires.iter().max_by_key(|(v, ..)| *v)
+            {
+                return Err(ThotError::layout(format!(
+                    "Stage 2 overflows into the Noun segment at 0xA000 ({} bytes, limit {limite}): \
+                     the IDT grows to {entrees} entries ({} bytes) for vector {vecteur}",
+                    taille,
+                    idt.len()
+                ))
+                .at(*span)
+                .with_help(
+                    "IRQs are usually remapped to vectors 0x20-0x2F: a lower vector keeps the IDT small",
+                ));
+            }
+
             // Patch LGDT / LIDT displacements (real-mode absolute addresses)
             if let Some(off) = pmode_lgdt_patch {
                 let addr = (base_stage2 + gdtr_offset as isize) as u16;
@@ -2122,6 +3530,12 @@ impl Emitter {
                 stage2_code[off] = (addr & 0xFF) as u8;
                 stage2_code[off + 1] = (addr >> 8) as u8;
             }
+        } else if let Some((_, _, span)) = self.gestionnaires.first() {
+            return Err(ThotError::layout(
+                "'nehes' fills the IDT of the protected mode, but there is no 'noyau'",
+            )
+            .at(*span)
+            .with_help("declare interrupt handlers in a tablet with a 'noyau:' label"));
         }
         // --- LE PATCHING ---
         for patch in &self.jump {
@@ -2135,32 +3549,52 @@ impl Emitter {
             } else {
                 &mut stage1_code
             };
-            if let Expression::Identifier(name) = &patch.target {
-                let addr = self
-                    .labels
-                    .get(name.as_str())
-                    .unwrap_or_else(|| panic!("Label missing : '{name}'"));
-                let dist = addr - (base + patch.offset as isize + patch.size as isize);
-                if patch.size == 4 {
-                    let b = (dist as i32).to_le_bytes();
-                    buffer[patch.offset] = b[0];
-                    buffer[patch.offset + 1] = b[1];
-                    buffer[patch.offset + 2] = b[2];
-                    buffer[patch.offset + 3] = b[3];
-                } else {
-                    if dist < i16::MIN as isize || dist > i16::MAX as isize {
-                        panic!("Jump out of range in real mode for label '{name}'");
-                    }
-                    let b = (dist as i16).to_le_bytes();
-                    buffer[patch.offset] = b[0];
-                    buffer[patch.offset + 1] = b[1];
+            let here = base + patch.origin as isize;
+            let lookup = |name: &str| {
+                self.labels
+                    .get(name)
+                    .map(|addr| *addr as i64)
+                    .or_else(|| self.variables.get(name).map(|addr| *addr as i64))
+            };
+            let value =
+                evaluate(&patch.target, here as i64, &lookup).map_err(|e| e.at(patch.span))?;
+            let value = match patch.kind {
+                PatchKind::Relative => {
+                    value - (base + patch.offset as isize + patch.size as isize) as i64
                 }
-            }
+                PatchKind::Absolute => value,
+            };
+            let bytes = if patch.kind == PatchKind::Relative && patch.size == 2 {
+                if value < i16::MIN as i64 || value > i16::MAX as i64 {
+                    return Err(ThotError::layout(format!(
+                        "Jump out of range in real mode: distance {value} does not fit in 16 bits"
+                    ))
+                    .at(patch.span));
+                }
+                (value as i16).to_le_bytes().to_vec()
+            } else {
+                // Distance signée ou adresse non signée : les deux doivent tenir dans l'emplacement
+                if !fits(value, patch.size) {
+                    return Err(ThotError::layout(format!(
+                        "Patched value {value} does not fit in {} bits",
+                        patch.size * 8
+                    ))
+                    .at(patch.span));
+                }
+                value.to_le_bytes()[..patch.size].to_vec()
+            };
+            buffer[patch.offset..patch.offset + patch.size].copy_from_slice(&bytes);
         }
         // --- FUSION FINALE DES MONDES ---
         let mut binaire_final = stage1_code;
         if is_bootloader {
             // Mode OS (Bootloader) : Alignements stricts pour le matériel
+            if binaire_final.len() > 510 {
+                return Err(ThotError::layout(format!(
+                    "Stage 1 is {} bytes but the boot sector only holds 510",
+                    binaire_final.len()
+                )));
+            }
             while binaire_final.len() < 510 {
                 binaire_final.push(0);
             }
@@ -2172,6 +3606,12 @@ impl Emitter {
             // --- LA GRANDE CORRECTION EST ICI ---
             // 0xA000 (RAM) - 0x7C00 (Boot) = 0x2400 (soit 9216 octets d'écart)
             // On remplit de vide jusqu'à atteindre l'endroit exact du Noun
+            if binaire_final.len() > 9216 {
+                return Err(ThotError::layout(format!(
+                    "Stage 2 overflows into the Noun segment at 0xA000 ({} bytes, limit 9216)",
+                    binaire_final.len()
+                )));
+            }
             while binaire_final.len() < 9216 {
                 binaire_final.push(0);
             }
@@ -2190,6 +3630,40 @@ impl Emitter {
             binaire_final.extend(self.segment_noun.clone());
         }
 
-        binaire_final
+        Ok(binaire_final)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_or_long_jumps() {
+        // Distances comptées depuis la fin du saut court
+        assert_eq!(saut_relatif(0x75, -128), [0x75, 0x80]);
+        assert_eq!(saut_relatif(0x75, 127), [0x75, 0x7F]);
+        // En arrière, la forme longue recule encore de ses 4 octets de plus
+        assert_eq!(
+            saut_relatif(0x75, -129),
+            [0x0F, 0x85, 0x7B, 0xFF, 0xFF, 0xFF]
+        );
+        assert_eq!(saut_relatif(0x74, 128), [0x0F, 0x84, 0x80, 0, 0, 0]);
+        // JMP rel32 (E9) n'a que 3 octets de plus que JMP rel8
+        assert_eq!(saut_relatif(0xEB, -129), [0xE9, 0x7C, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(
+            sauter_si(0x74, &[0x90; 200])[..6],
+            [0x0F, 0x84, 200, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn division_loop_reaches_back() {
+        // La boucle de bits de Zenith (quatre dwords par canal) tient dans un rel8
+        let canal = division_canal(16);
+        assert_eq!(canal[canal.len() - 2], 0x75);
+        let debut = canal.iter().position(|&o| o == 0xB1).unwrap() + 2;
+        let retour = canal[canal.len() - 1] as i8 as isize;
+        assert_eq!(canal.len() as isize + retour, debut as isize);
     }
 }
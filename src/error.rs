@@ -0,0 +1,127 @@
+use crate::source::Span;
+use std::fmt;
+
+/// Les grandes familles d'Isfet (désordre) que le Scribe sait reconnaître.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Caractère ou littéral que les Yeux (Lexer) ne savent pas lire.
+    Lexical,
+    /// Suite de jetons que l'Esprit (Parser) ou l'Émetteur ne comprend pas.
+    Syntax,
+    /// Registres de tailles différentes, ou taille non supportée par un verbe.
+    SizeMismatch,
+    /// Valeur qui ne tient pas dans son vaisseau.
+    Overflow,
+    /// Étiquette ou variable introuvable lors du patching.
+    UndefinedLabel,
+    /// Tablette illisible ou binaire impossible à écrire.
+    Io,
+    /// Le binaire final ne respecte pas la carte mémoire (secteurs, Noun, sauts).
+    Layout,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self {
+            ErrorKind::Lexical => "lexical",
+            ErrorKind::Syntax => "syntax",
+            ErrorKind::SizeMismatch => "size mismatch",
+            ErrorKind::Overflow => "overflow",
+            ErrorKind::UndefinedLabel => "undefined label",
+            ErrorKind::Io => "I/O",
+            ErrorKind::Layout => "layout",
+        };
+        write!(f, "{label}")
+    }
+}
+
+/// Une erreur de compilation : sa famille, son message, si connue sa position,
+/// et éventuellement un conseil pour réparer la tablette et une seconde position
+/// à montrer (la première définition d'un symbole en double, par exemple).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThotError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<String>,
+    // En boîte : l'erreur voyage dans tous les `Result`, elle doit rester légère
+    pub note: Option<Box<(String, Span)>>,
+}
+
+pub type ThotResult<T> = Result<T, ThotError>;
+
+impl ThotError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        ThotError {
+            kind,
+            message: message.into(),
+            span: None,
+            help: None,
+            note: None,
+        }
+    }
+
+    pub fn lexical(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::Lexical, message)
+    }
+
+    pub fn syntax(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::Syntax, message)
+    }
+
+    pub fn size_mismatch(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::SizeMismatch, message)
+    }
+
+    pub fn overflow(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::Overflow, message)
+    }
+
+    pub fn undefined_label(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::UndefinedLabel, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::Io, message)
+    }
+
+    pub fn layout(message: impl Into<String>) -> Self {
+        ThotError::new(ErrorKind::Layout, message)
+    }
+
+    /// Attache une position à l'erreur, sauf si une position plus précise est déjà connue.
+    /// Les vérifications de registres ne connaissent pas leur ligne : c'est l'appelant
+    /// (Parser ou Émetteur) qui la fournit en remontant.
+    pub fn at(mut self, span: Span) -> Self {
+        if self.span.is_none() {
+            self.span = Some(span);
+        }
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>, span: Span) -> Self {
+        self.note = Some(Box::new((note.into(), span)));
+        self
+    }
+}
+
+impl fmt::Display for ThotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} error: {}", self.kind, self.message)
+    }
+}
+
+impl std::error::Error for ThotError {}
+
+// Les étapes qui rapportent plusieurs erreurs d'un coup (Parser, Tisserand)
+// acceptent aussi `?` sur une erreur isolée.
+impl From<ThotError> for Vec<ThotError> {
+    fn from(erreur: ThotError) -> Self {
+        vec![erreur]
+    }
+}
@@ -0,0 +1,330 @@
+use crate::ast::Expression;
+use crate::error::{ThotError, ThotResult};
+use crate::lexer::Token;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Les opérateurs binaires que Thot sait résoudre à la compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    And,
+    Xor,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Les opérateurs unaires : `-x` et `~x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl BinaryOp {
+    pub fn from_token(token: &Token) -> Option<Self> {
+        let op = match token {
+            Token::Star => BinaryOp::Mul,
+            Token::Slash => BinaryOp::Div,
+            Token::Percent => BinaryOp::Rem,
+            Token::Plus => BinaryOp::Add,
+            Token::Minus => BinaryOp::Sub,
+            Token::ShiftLeft => BinaryOp::Shl,
+            Token::ShiftRight => BinaryOp::Shr,
+            Token::Ampersand => BinaryOp::And,
+            Token::Caret => BinaryOp::Xor,
+            Token::Pipe => BinaryOp::Or,
+            Token::EqualEqual => BinaryOp::Eq,
+            Token::NotEqual => BinaryOp::Ne,
+            Token::Less => BinaryOp::Lt,
+            Token::LessEqual => BinaryOp::Le,
+            Token::Greater => BinaryOp::Gt,
+            Token::GreaterEqual => BinaryOp::Ge,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Plus le nombre est grand, plus l'opérateur lie fort (même ordre que Rust).
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 6,
+            BinaryOp::Add | BinaryOp::Sub => 5,
+            BinaryOp::Shl | BinaryOp::Shr => 4,
+            BinaryOp::And => 3,
+            BinaryOp::Xor => 2,
+            BinaryOp::Or => 1,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 0,
+        }
+    }
+
+    /// Applique l'opérateur. Les débordements et la division par zéro sont des erreurs,
+    /// jamais un résultat silencieusement faux. Les comparaisons valent 1 ou 0.
+    pub fn apply(self, left: i64, right: i64) -> ThotResult<i64> {
+        let overflow = || {
+            ThotError::overflow(format!(
+                "Arithmetic overflow in constant expression: {left} {self} {right}"
+            ))
+        };
+        let result = match self {
+            BinaryOp::Add => left.checked_add(right).ok_or_else(overflow)?,
+            BinaryOp::Sub => left.checked_sub(right).ok_or_else(overflow)?,
+            BinaryOp::Mul => left.checked_mul(right).ok_or_else(overflow)?,
+            BinaryOp::Div | BinaryOp::Rem => {
+                if right == 0 {
+                    return Err(ThotError::overflow(format!(
+                        "Division by zero in constant expression: {left} {self} {right}"
+                    )));
+                }
+                if self == BinaryOp::Div {
+                    left.checked_div(right).ok_or_else(overflow)?
+                } else {
+                    left.checked_rem(right).ok_or_else(overflow)?
+                }
+            }
+            BinaryOp::Shl | BinaryOp::Shr => {
+                let shift = u32::try_from(right)
+                    .ok()
+                    .filter(|s| *s < i64::BITS)
+                    .ok_or_else(|| {
+                        ThotError::overflow(format!(
+                            "Shift amount out of range (0 to 63): {left} {self} {right}"
+                        ))
+                    })?;
+                if self == BinaryOp::Shl {
+                    left.checked_shl(shift).ok_or_else(overflow)?
+                } else {
+                    left >> shift
+                }
+            }
+            BinaryOp::And => left & right,
+            BinaryOp::Xor => left ^ right,
+            BinaryOp::Or => left | right,
+            BinaryOp::Eq => (left == right) as i64,
+            BinaryOp::Ne => (left != right) as i64,
+            BinaryOp::Lt => (left < right) as i64,
+            BinaryOp::Le => (left <= right) as i64,
+            BinaryOp::Gt => (left > right) as i64,
+            BinaryOp::Ge => (left >= right) as i64,
+        };
+        Ok(result)
+    }
+}
+
+impl UnaryOp {
+    pub fn apply(self, value: i64) -> ThotResult<i64> {
+        match self {
+            UnaryOp::Neg => value.checked_neg().ok_or_else(|| {
+                ThotError::overflow(format!(
+                    "Arithmetic overflow in constant expression: -({value})"
+                ))
+            }),
+            UnaryOp::Not => Ok(!value),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::And => "&",
+            BinaryOp::Xor => "^",
+            BinaryOp::Or => "|",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnaryOp::Neg => write!(f, "-"),
+            UnaryOp::Not => write!(f, "~"),
+        }
+    }
+}
+
+/// Résout une expression une fois toutes les adresses connues.
+/// `here` est l'adresse de l'instruction courante (`$`), `lookup` trouve labels et variables.
+pub fn evaluate(
+    expr: &Expression,
+    here: i64,
+    lookup: &dyn Fn(&str) -> Option<i64>,
+) -> ThotResult<i64> {
+    match expr {
+        Expression::Number(n) => Ok(*n),
+        Expression::CurrentAddress => Ok(here),
+        Expression::Identifier(name) => lookup(name)
+            .ok_or_else(|| ThotError::undefined_label(format!("Label missing : '{name}'"))),
+        Expression::Binary { op, left, right } => op.apply(
+            evaluate(left, here, lookup)?,
+            evaluate(right, here, lookup)?,
+        ),
+        Expression::Unary { op, operand } => op.apply(evaluate(operand, here, lookup)?),
+        other => Err(ThotError::syntax(format!(
+            "{other:?} cannot be resolved to an address"
+        ))),
+    }
+}
+
+/// Remplace les constantes connues par leur valeur et replie ce qui devient calculable.
+/// Les labels et '$' restent en place pour l'édition des liens.
+pub fn substitute(expr: &Expression, constants: &HashMap<String, i64>) -> ThotResult<Expression> {
+    let resultat = match expr {
+        Expression::Identifier(name) => match constants.get(name) {
+            Some(valeur) => Expression::Number(*valeur),
+            None => expr.clone(),
+        },
+        Expression::Binary { op, left, right } => {
+            match (substitute(left, constants)?, substitute(right, constants)?) {
+                (Expression::Number(l), Expression::Number(r)) => {
+                    Expression::Number(op.apply(l, r)?)
+                }
+                (l, r) => Expression::Binary {
+                    op: *op,
+                    left: Box::new(l),
+                    right: Box::new(r),
+                },
+            }
+        }
+        Expression::Unary { op, operand } => match substitute(operand, constants)? {
+            Expression::Number(n) => Expression::Number(op.apply(n)?),
+            autre => Expression::Unary {
+                op: *op,
+                operand: Box::new(autre),
+            },
+        },
+        Expression::Memory(adresse) => {
+            let mut adresse = adresse.clone();
+            adresse.displacement = substitute(&adresse.displacement, constants)?;
+            Expression::Memory(adresse)
+        }
+        _ => expr.clone(),
+    };
+    Ok(resultat)
+}
+
+/// Les noms encore inconnus d'une expression (labels, variables, constantes à venir).
+pub fn identifiers(expr: &Expression) -> Vec<&str> {
+    match expr {
+        Expression::Identifier(name) => vec![name.as_str()],
+        Expression::Binary { left, right, .. } => {
+            let mut noms = identifiers(left);
+            noms.extend(identifiers(right));
+            noms
+        }
+        Expression::Unary { operand, .. } => identifiers(operand),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    fn nom(nom: &str) -> Expression {
+        Expression::Identifier(nom.to_string())
+    }
+
+    fn binaire(op: BinaryOp, left: Expression, right: Expression) -> Expression {
+        Expression::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    // Une expression laissée à l'édition des liens, avec debut = 0x7E00, fin = 0x7E40 et $ = 0x7E10
+    fn resoudre(expression: &Expression) -> ThotResult<i64> {
+        let lookup = |nom: &str| match nom {
+            "debut" => Some(0x7E00),
+            "fin" => Some(0x7E40),
+            _ => None,
+        };
+        evaluate(expression, 0x7E10, &lookup)
+    }
+
+    #[test]
+    fn deferred_expressions() {
+        let taille = binaire(BinaryOp::Sub, nom("fin"), nom("debut"));
+        assert_eq!(resoudre(&taille).unwrap(), 0x40);
+        let quart = binaire(BinaryOp::Shr, taille.clone(), Expression::Number(2));
+        assert_eq!(resoudre(&quart).unwrap(), 0x10);
+        let ici = binaire(BinaryOp::Sub, Expression::CurrentAddress, nom("debut"));
+        assert_eq!(resoudre(&ici).unwrap(), 0x10);
+        let oppose = Expression::Unary {
+            op: UnaryOp::Neg,
+            operand: Box::new(nom("debut")),
+        };
+        assert_eq!(resoudre(&oppose).unwrap(), -0x7E00);
+        let plus_loin = binaire(BinaryOp::Gt, nom("fin"), nom("debut"));
+        assert_eq!(resoudre(&plus_loin).unwrap(), 1);
+    }
+
+    #[test]
+    fn deferred_division_by_zero() {
+        let vide = binaire(BinaryOp::Sub, nom("debut"), nom("debut"));
+        for op in [BinaryOp::Div, BinaryOp::Rem] {
+            let e = resoudre(&binaire(op, nom("fin"), vide.clone())).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::Overflow);
+            assert!(e.message.contains("Division by zero"), "{}", e.message);
+        }
+        let taille = binaire(BinaryOp::Sub, nom("fin"), nom("debut"));
+        let e = resoudre(&binaire(BinaryOp::Shl, nom("fin"), taille)).unwrap_err();
+        assert!(e.message.contains("Shift amount out of range"));
+    }
+
+    #[test]
+    fn missing_label() {
+        let e = resoudre(&binaire(BinaryOp::Add, nom("fin"), nom("absent"))).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::UndefinedLabel);
+        assert!(e.message.contains("'absent'"));
+    }
+
+    #[test]
+    fn substitution_folds_constants() {
+        // LARGEUR * 2 + fin : la partie constante se replie, le label reste
+        let produit = binaire(BinaryOp::Mul, nom("LARGEUR"), Expression::Number(2));
+        let expression = binaire(BinaryOp::Add, produit, nom("fin"));
+        let constantes = HashMap::from([("LARGEUR".to_string(), 80)]);
+        assert_eq!(
+            substitute(&expression, &constantes).unwrap(),
+            binaire(BinaryOp::Add, Expression::Number(160), nom("fin"))
+        );
+        // Une constante nulle qui devient diviseur est une erreur, pas un résultat faux
+        let zero = HashMap::from([("LARGEUR".to_string(), 0)]);
+        let quotient = binaire(BinaryOp::Div, Expression::Number(8), nom("LARGEUR"));
+        let e = substitute(&quotient, &zero).unwrap_err();
+        assert!(e.message.contains("Division by zero"));
+    }
+}
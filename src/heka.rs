@@ -0,0 +1,178 @@
+use crate::ast::{Expression, Instruction};
+use crate::error::{ThotError, ThotResult};
+use crate::source::{Span, Spanned};
+use std::collections::HashMap;
+
+// Au-delà, une heka s'appelle elle-même (directement ou non) : on abandonne
+const PROFONDEUR_MAX: usize = 64;
+
+struct Heka {
+    parametres: Vec<String>,
+    corps: Vec<Spanned<Instruction>>,
+}
+
+struct Depliage {
+    definitions: HashMap<String, Heka>,
+    // Numéro de chaque dépliage, pour des labels uniques
+    compteur: usize,
+    erreurs: Vec<ThotError>,
+}
+
+/// Déplie les macros `heka` du programme aplati : chaque appel est remplacé par le corps
+/// de la macro, paramètres substitués et labels renommés pour que deux appels ne se
+/// marchent pas dessus. Les instructions dépliées portent la position de l'appel.
+pub fn expand(
+    instructions: Vec<Spanned<Instruction>>,
+) -> Result<Vec<Spanned<Instruction>>, Vec<ThotError>> {
+    // 1. Collecte des définitions : une heka peut servir avant d'être définie
+    let mut depliage = Depliage {
+        definitions: HashMap::new(),
+        compteur: 0,
+        erreurs: Vec::new(),
+    };
+    let mut programme = Vec::new();
+    for instruction in instructions {
+        match instruction.node {
+            Instruction::Heka {
+                nom,
+                parametres,
+                corps,
+            } => {
+                if let Err(erreur) = depliage.definir(nom, parametres, corps) {
+                    depliage.erreurs.push(erreur.at(instruction.span));
+                }
+            }
+            autre => programme.push(Spanned::new(autre, instruction.span)),
+        }
+    }
+
+    // 2. Dépliage des appels
+    let mut sortie = Vec::new();
+    depliage.deplier(programme, &mut sortie, 0, None);
+    if depliage.erreurs.is_empty() {
+        Ok(sortie)
+    } else {
+        Err(depliage.erreurs)
+    }
+}
+
+impl Depliage {
+    fn definir(
+        &mut self,
+        nom: String,
+        parametres: Vec<String>,
+        corps: Vec<Spanned<Instruction>>,
+    ) -> ThotResult<()> {
+        if self.definitions.contains_key(&nom) {
+            return Err(ThotError::syntax(format!("heka '{nom}' is defined twice")));
+        }
+        let ombre = corps
+            .iter()
+            .find_map(|instruction| match &instruction.node {
+                Instruction::Label(label) if parametres.contains(label) => Some(label),
+                _ => None,
+            });
+        if let Some(label) = ombre {
+            return Err(ThotError::syntax(format!(
+                "Label '{label}' in heka '{nom}' has the same name as a parameter"
+            )));
+        }
+        self.definitions.insert(nom, Heka { parametres, corps });
+        Ok(())
+    }
+
+    // `appel` est la position de l'appel le plus extérieur : c'est elle que verra le Scribe
+    fn deplier(
+        &mut self,
+        instructions: Vec<Spanned<Instruction>>,
+        sortie: &mut Vec<Spanned<Instruction>>,
+        profondeur: usize,
+        appel: Option<Span>,
+    ) {
+        for instruction in instructions {
+            let span = appel.unwrap_or(instruction.span);
+            match instruction.node {
+                Instruction::Invocation { nom, arguments } => {
+                    match self.instancier(&nom, arguments, profondeur) {
+                        Ok(corps) => self.deplier(corps, sortie, profondeur + 1, Some(span)),
+                        Err(erreur) => self.erreurs.push(erreur.at(span)),
+                    }
+                }
+                autre => sortie.push(Spanned::new(autre, span)),
+            }
+        }
+    }
+
+    // Une copie du corps, paramètres remplacés par les arguments et labels renommés
+    fn instancier(
+        &mut self,
+        nom: &str,
+        arguments: Vec<Expression>,
+        profondeur: usize,
+    ) -> ThotResult<Vec<Spanned<Instruction>>> {
+        let heka = self.definitions.get(nom).ok_or_else(|| {
+            ThotError::syntax(format!("'{nom}' is neither a heka nor a label"))
+                .with_help(format!("a label is followed by a colon: '{nom}:'"))
+        })?;
+        if profondeur >= PROFONDEUR_MAX {
+            return Err(ThotError::syntax(format!(
+                "heka '{nom}' expands into itself endlessly"
+            )));
+        }
+        if arguments.len() != heka.parametres.len() {
+            return Err(ThotError::syntax(format!(
+                "heka '{nom}' takes {} argument(s) but {} were given",
+                heka.parametres.len(),
+                arguments.len()
+            )));
+        }
+
+        self.compteur += 1;
+        let mut table: HashMap<String, Expression> =
+            heka.parametres.iter().cloned().zip(arguments).collect();
+        let mut renommes = HashMap::new();
+        for instruction in &heka.corps {
+            if let Instruction::Label(label) = &instruction.node {
+                // '@' n'existe pas dans un identifiant Maât : aucune collision possible
+                let unique = format!("{label}@{nom}{}", self.compteur);
+                table.insert(label.clone(), Expression::Identifier(unique.clone()));
+                renommes.insert(label.clone(), unique);
+            }
+        }
+
+        let mut corps = heka.corps.clone();
+        for instruction in &mut corps {
+            if let Instruction::Label(label) = &mut instruction.node
+                && let Some(unique) = renommes.get(label)
+            {
+                *label = unique.clone();
+            }
+            for expression in instruction.node.expressions_mut() {
+                *expression = remplacer(expression, &table);
+            }
+        }
+        Ok(corps)
+    }
+}
+
+// Remplace les noms de la table partout dans l'expression
+fn remplacer(expr: &Expression, table: &HashMap<String, Expression>) -> Expression {
+    match expr {
+        Expression::Identifier(name) => table.get(name).cloned().unwrap_or_else(|| expr.clone()),
+        Expression::Binary { op, left, right } => Expression::Binary {
+            op: *op,
+            left: Box::new(remplacer(left, table)),
+            right: Box::new(remplacer(right, table)),
+        },
+        Expression::Unary { op, operand } => Expression::Unary {
+            op: *op,
+            operand: Box::new(remplacer(operand, table)),
+        },
+        Expression::Memory(adresse) => {
+            let mut adresse = adresse.clone();
+            adresse.displacement = remplacer(&adresse.displacement, table);
+            Expression::Memory(adresse)
+        }
+        _ => expr.clone(),
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::{ThotError, ThotResult};
+use crate::source::{Span, Spanned};
 use std::iter::Peekable;
 use std::str::Chars;
 
@@ -13,9 +15,10 @@ pub enum Token {
     Identifier(String), // e.g., "effort", "Helix"
 
     // Pure values
-    Number(i32),
-    Helix(u16, u16),
+    Number(i64),
+    Helix(u128, u128),
     StringLiteral(String), // e.g., "Alert !"
+    SystemPath(String),    // e.g., <std/print>, lu tel quel après 'dema'
 
     // Punctuation
     Comma,      // ,
@@ -24,11 +27,24 @@ pub enum Token {
     OpenParen,  // (
     CloseParen, // )
     Equals,
-    Plus,   // +
-    Minus,  // -
-    Star,   // *
-    Slash,  // /
-    Dollar, // $ (Current address)
+    Plus,         // +
+    Minus,        // -
+    Star,         // *
+    Slash,        // /
+    Dollar,       // $ (Current address)
+    Percent,      // % (modulo, quand il n'annonce pas un registre)
+    Ampersand,    // &
+    Pipe,         // |
+    Caret,        // ^
+    Tilde,        // ~
+    ShiftLeft,    // <<
+    ShiftRight,   // >>
+    EqualEqual,   // ==
+    NotEqual,     // !=
+    Less,         // <
+    LessEqual,    // <=
+    Greater,      // >
+    GreaterEqual, // >=
     // End of File
     Eof,
     OpenBracket,
@@ -38,56 +54,229 @@ pub enum Token {
 #[derive(Clone)]
 pub struct Lexer<'a> {
     input: Peekable<Chars<'a>>,
+    file: usize,
+    line: usize,
+    column: usize,
+    // Le jeton précédent était 'dema' : un '<' ouvre alors un chemin système
+    after_dema: bool,
 }
 
 impl<'a> Lexer<'a> {
-    // Initializes Thot with the source code
-    pub fn new(source: &'a str) -> Self {
+    // Initializes Thot with the source code of the tablet registered under `file`
+    pub fn new(source: &'a str, file: usize) -> Self {
         Lexer {
             input: source.chars().peekable(),
+            file,
+            line: 1,
+            column: 1,
+            after_dema: false,
         }
     }
 
-    // Extracts the next Token
-    pub fn next_token(&mut self) -> Token {
-        // 1. Skip whitespace and newlines
+    // Consumes one character while keeping track of the line and column
+    fn bump(&mut self) -> Option<char> {
+        let c = self.input.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    // Reads the rest of a name. A '.' followed by a letter stays inside the name,
+    // so that 'global.local' designates a local label from outside its scope.
+    fn read_word(&mut self, first: char) -> String {
+        let mut word = first.to_string();
+        while let Some(&next_char) = self.input.peek() {
+            let local = next_char == '.'
+                && self
+                    .input
+                    .clone()
+                    .nth(1)
+                    .is_some_and(|n| n.is_alphabetic() || n == '_');
+            if next_char.is_alphanumeric() || next_char == '_' || local {
+                word.push(self.bump().unwrap());
+            } else {
+                break;
+            }
+        }
+        word
+    }
+
+    fn position(&self) -> Span {
+        Span::new(self.file, self.line, self.column)
+    }
+
+    // Extracts the next Token together with the position where it starts
+    pub fn next_token(&mut self) -> ThotResult<Spanned<Token>> {
+        // 1. Skip whitespace, newlines and comments
         while let Some(&c) = self.input.peek() {
             if c.is_whitespace() {
-                self.input.next();
+                self.bump();
+            } else if c == ';' {
+                // C'est un Murmure ! On ignore tout jusqu'à la fin de la ligne.
+                while let Some(&next_char) = self.input.peek() {
+                    if next_char != '\n' {
+                        self.bump(); // On "mange" le caractère sans rien en faire
+                    } else {
+                        break; // On s'arrête au saut de ligne
+                    }
+                }
             } else {
                 break;
             }
         }
 
         // 2. Look at the current character
-        let c = match self.input.next() {
+        let span = self.position();
+        let c = match self.bump() {
             Some(c) => c,
-            None => return Token::Eof,
+            None => return Ok(Spanned::new(Token::Eof, span)),
+        };
+        let token = if c == '<' && self.after_dema {
+            self.read_system_path().map_err(|e| e.at(span))?
+        } else {
+            self.classify(c).map_err(|e| e.at(span))?
         };
+        self.after_dema = matches!(&token, Token::Verb(v) if v == "dema");
+        Ok(Spanned::new(token, span))
+    }
+
+    // dema <std/print> : tout ce qui est entre '<' et '>' forme le chemin, sans être découpé
+    // en jetons (01_boot, a.b ou v0x10 sont des noms de fichier comme les autres)
+    fn read_system_path(&mut self) -> ThotResult<Token> {
+        let mut path = String::new();
+        loop {
+            match self.input.peek() {
+                Some('>') => {
+                    self.bump();
+                    break;
+                }
+                Some('\n') | None => {
+                    return Err(ThotError::lexical("Unterminated system path")
+                        .with_help("close the tablet name with '>': dema <std/print>"));
+                }
+                Some(_) => path.extend(self.bump()),
+            }
+        }
+        if path.is_empty() {
+            return Err(ThotError::lexical("Empty system path")
+                .with_help("name the tablet between '<' and '>': dema <std/print>"));
+        }
+        Ok(Token::SystemPath(path))
+    }
+
+    // Consomme le caractère attendu s'il suit (pour les opérateurs à deux caractères)
+    fn eat(&mut self, expected: char) -> bool {
+        if self.input.peek() == Some(&expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    // Lit un entier : décimal, 0x (hexadécimal), 0b (binaire) ou 0o (octal).
+    // Les '_' ne sont que des séparateurs visuels : 1_000_000, 0b1010_0101.
+    fn read_integer(&mut self, first: char) -> ThotResult<u128> {
+        let mut radix = 10;
+        if first == '0' {
+            radix = match self.input.peek() {
+                Some('x' | 'X') => 16,
+                Some('b' | 'B') => 2,
+                Some('o' | 'O') => 8,
+                _ => 10,
+            };
+            if radix != 10 {
+                self.bump(); // On mange le préfixe
+            }
+        }
+        let mut digits = String::new();
+        if radix == 10 {
+            digits.push(first);
+        }
+        while let Some(&next_char) = self.input.peek() {
+            if next_char == '_' {
+                self.bump();
+            } else if next_char.is_digit(radix) {
+                digits.push(next_char);
+                self.bump();
+            } else if next_char.is_ascii_alphanumeric() {
+                return Err(ThotError::lexical(format!(
+                    "Invalid digit '{next_char}' in base-{radix} literal"
+                )));
+            } else {
+                break;
+            }
+        }
+        if digits.is_empty() {
+            return Err(ThotError::lexical(format!(
+                "Missing digits after the base-{radix} prefix"
+            )));
+        }
+        // Jusqu'à 128 bits : la largeur d'une force de Helix Zenith
+        u128::from_str_radix(&digits, radix)
+            .map_err(|_| ThotError::overflow(format!("Literal {digits} does not fit in 128 bits")))
+    }
+
+    fn classify(&mut self, c: char) -> ThotResult<Token> {
         // 3. Classify the character
-        match c {
-            '=' => Token::Equals,
+        let token = match c {
+            '=' => {
+                if self.eat('=') {
+                    Token::EqualEqual
+                } else {
+                    Token::Equals
+                }
+            }
+            '&' => Token::Ampersand,
+            '|' => Token::Pipe,
+            '^' => Token::Caret,
+            '~' => Token::Tilde,
+            '<' => {
+                if self.eat('<') {
+                    Token::ShiftLeft
+                } else if self.eat('=') {
+                    Token::LessEqual
+                } else {
+                    Token::Less
+                }
+            }
+            '>' => {
+                if self.eat('>') {
+                    Token::ShiftRight
+                } else if self.eat('=') {
+                    Token::GreaterEqual
+                } else {
+                    Token::Greater
+                }
+            }
+            '!' => {
+                if !self.eat('=') {
+                    return Err(ThotError::lexical("Expected '=' after '!'")
+                        .with_help("use '~' for bitwise NOT, or '!=' to compare"));
+                }
+                Token::NotEqual
+            }
             '+' => Token::Plus,
             '$' => Token::Dollar,
             '-' => Token::Minus,
             '*' => Token::Star,
             '/' => Token::Slash,
-            ';' => {
-                // C'est un Murmure ! On ignore tout jusqu'à la fin de la ligne.
-                while let Some(&next_char) = self.input.peek() {
-                    if next_char != '\n' {
-                        self.input.next(); // On "mange" le caractère sans rien en faire
-                    } else {
-                        break; // On s'arrête au saut de ligne
-                    }
-                }
-                // La ligne est finie, on relance la machine pour chercher le VRAI prochain jeton
-                self.next_token()
-            }
             '[' => Token::OpenBracket,
             ']' => Token::CloseBracket,
             ',' => Token::Comma,
             ':' => Token::Colon,
+            // '.boucle' : un label local, rattaché au dernier label global
+            '.' if self
+                .input
+                .peek()
+                .is_some_and(|n| n.is_alphabetic() || *n == '_') =>
+            {
+                Token::Identifier(self.read_word('.'))
+            }
             '.' => Token::Dot,
             '(' => Token::OpenParen,
             ')' => Token::CloseParen,
@@ -95,10 +284,10 @@ impl<'a> Lexer<'a> {
                 let mut string_content = String::new();
                 while let Some(&next_char) = self.input.peek() {
                     if next_char != '"' {
-                        let c = self.input.next().unwrap();
+                        let c = self.bump().unwrap();
                         // --- LE DÉTECTEUR D'ÉCHAPPEMENT ---
                         if c == '\\' {
-                            if let Some(escaped) = self.input.next() {
+                            if let Some(escaped) = self.bump() {
                                 match escaped {
                                     'n' => string_content.push('\n'), // 0x0A
                                     'r' => string_content.push('\r'), // 0x0D
@@ -112,18 +301,20 @@ impl<'a> Lexer<'a> {
                             string_content.push(c);
                         }
                     } else {
-                        self.input.next(); // Ferme les guillemets
+                        self.bump(); // Ferme les guillemets
                         break;
                     }
                 }
                 Token::StringLiteral(string_content)
             }
+            // A '%' not followed by a letter -> modulo (ex: "10 % 3")
+            '%' if !self.input.peek().is_some_and(|c| c.is_alphabetic()) => Token::Percent,
             // If it's a '%' -> It's a Sacred Register!
             '%' => {
                 let mut name = String::new();
                 while let Some(&next_char) = self.input.peek() {
                     if next_char.is_alphabetic() {
-                        name.push(self.input.next().unwrap());
+                        name.push(self.bump().unwrap());
                     } else {
                         break;
                     }
@@ -132,91 +323,72 @@ impl<'a> Lexer<'a> {
             }
             // Si c'est un chiffre -> C'est le début d'un HELIX (Ra:Apophis)
             '0'..='9' => {
-                let mut is_hex = false;
-                if c == '0'
-                    && let Some(&next_char) = self.input.peek()
-                    && (next_char == 'x' || next_char == 'X')
-                {
-                    is_hex = true;
-                    self.input.next(); // On mange le 'x'
-                }
-
                 // 1. On lit la première force (Ra)
-                let mut ra_str = String::new();
-                if !is_hex {
-                    ra_str.push(c);
-                }
-                while let Some(&next_char) = self.input.peek() {
-                    if (is_hex && next_char.is_ascii_hexdigit())
-                        || (!is_hex && next_char.is_ascii_digit())
-                    {
-                        ra_str.push(self.input.next().unwrap());
-                    } else {
-                        break;
-                    }
-                }
-
-                let ra_val = if is_hex {
-                    u16::from_str_radix(&ra_str, 16).unwrap()
-                } else {
-                    ra_str.parse::<u16>().unwrap()
-                };
+                let ra_val = self.read_integer(c)?;
 
                 // 2. On cherche le point d'équilibre ':' (L'opposition)
                 if let Some(&':') = self.input.peek() {
-                    self.input.next(); // On mange le ':'
-
-                    // --- NOUVEAU CODE POUR APOPHIS (Gère l'hexadécimal) ---
-                    let mut apo_str = String::new();
-                    let mut apo_is_hex = false;
-
-                    // Détection du préfixe 0x pour Apophis
-                    if let Some(&'0') = self.input.peek() {
-                        self.input.next(); // Mange le '0'
-                        if let Some(&'x') | Some(&'X') = self.input.peek() {
-                            self.input.next(); // Mange le 'x'
-                            apo_is_hex = true;
-                        } else {
-                            apo_str.push('0'); // C'était un zéro normal
+                    self.bump(); // On mange le ':'
+
+                    // Apophis absent (ex: "5:") : la force opposée vaut zéro
+                    let apophis_val = match self.input.peek() {
+                        Some(&d) if d.is_ascii_digit() => {
+                            self.bump();
+                            self.read_integer(d)?
                         }
+                        _ => 0,
+                    };
+                    Token::Helix(ra_val, apophis_val)
+                } else {
+                    let n = i64::try_from(ra_val).map_err(|_| {
+                        ThotError::overflow(format!("Literal {ra_val} does not fit in 64 bits"))
+                    })?;
+                    Token::Number(n)
+                }
+            }
+            // 'A' -> Le code du caractère, comme un nombre
+            '\'' => {
+                let value = match self.bump() {
+                    Some('\\') => match self.bump() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some(escaped @ ('\\' | '\'' | '"')) => escaped,
+                        Some(other) => {
+                            return Err(ThotError::lexical(format!(
+                                "Unknown escape in character literal: \\{other}"
+                            )));
+                        }
+                        None => {
+                            return Err(ThotError::lexical("Unterminated character literal"));
+                        }
+                    },
+                    Some('\'') | Some('\n') | None => {
+                        return Err(ThotError::lexical("Empty character literal"));
                     }
-
+                    Some(ch) => ch,
+                };
+                if self.bump() != Some('\'') {
+                    // On avale le reste jusqu'à la fermeture pour ne pas semer d'autres erreurs
                     while let Some(&next_char) = self.input.peek() {
-                        if (apo_is_hex && next_char.is_ascii_hexdigit())
-                            || (!apo_is_hex && next_char.is_ascii_digit())
-                        {
-                            apo_str.push(self.input.next().unwrap());
-                        } else {
+                        if next_char == '\n' {
                             break;
                         }
-                    }
-
-                    let apophis_val = if !apo_str.is_empty() {
-                        if apo_is_hex {
-                            u16::from_str_radix(&apo_str, 16).unwrap()
-                        } else {
-                            apo_str.parse::<u16>().unwrap()
+                        if self.bump() == Some('\'') {
+                            break;
                         }
-                    } else {
-                        0
-                    };
-
-                    Token::Helix(ra_val, apophis_val)
-                    // --- FIN DU NOUVEAU CODE ---
-                } else {
-                    Token::Number(ra_val as i32)
+                    }
+                    return Err(ThotError::lexical(
+                        "Character literal must contain exactly one character",
+                    )
+                    .with_help("use double quotes for strings: \"...\""));
                 }
+                Token::Number(value as i64)
             }
             // If it's a letter -> It's a Verb or an Identifier
             'a'..='z' | 'A'..='Z' | '_' => {
-                let mut word = c.to_string();
-                while let Some(&next_char) = self.input.peek() {
-                    if next_char.is_alphanumeric() || next_char == '_' {
-                        word.push(self.input.next().unwrap());
-                    } else {
-                        break;
-                    }
-                }
+                let word = self.read_word(c);
 
                 // Check if it's a known Maât verb
                 match word.as_str() {
@@ -224,11 +396,159 @@ impl<'a> Lexer<'a> {
                     | "kheper" | "per" | "return" | "sedjem" | "wab" | "jena" | "isfet"
                     | "kheb" | "henet" | "mer" | "shesa" | "her" | "kher" | "her_ankh"
                     | "kher_ankh" | "dema" | "push" | "pop" | "in" | "out" | "nama" | "smen"
-                    | "rdtsc" | "kherp" | "dja" => Token::Verb(word),
+                    | "rdtsc" | "kherp" | "dja" | "heka" | "end" | "if" | "elif" | "else"
+                    | "sesh" | "msesh" | "hsesh" | "vsesh" | "djed" | "shu" | "heh" | "ini"
+                    | "export" | "private" | "pesh" | "maa" | "kem" | "hem" | "wer" | "nedjes"
+                    | "nedjes_ankh" | "deben" | "nebed" | "wep" | "wep_ankh" | "wah" | "hery"
+                    | "khery" | "hery_ankh" | "khery_ankh" | "fai" | "nen_fai" | "bah"
+                    | "nen_bah" | "tep" | "nen_tep" | "mety" | "nen_mety" | "renpet" | "tut"
+                    | "mtut" | "htut" | "meh" | "mmeh" | "hmeh" | "sia" | "msia" | "hsia"
+                    | "nehes" | "nis" | "iwi" | "khetem" | "wen" | "hotep" => {
+                        // Un verbe suivi de ':' : une tablette écrite avant que le mot soit réservé
+                        if self.input.peek() == Some(&':') {
+                            return Err(reserved(&word, "label"));
+                        }
+                        Token::Verb(word)
+                    }
                     _ => Token::Identifier(word), // Otherwise, it's a variable/type
                 }
             }
-            _ => panic!("Thot encountered an unknown character: {c}"),
+            _ => {
+                return Err(ThotError::lexical(format!(
+                    "Thot encountered an unknown character: {c}"
+                )));
+            }
+        };
+        Ok(token)
+    }
+}
+
+// Un verbe à la place d'un nom (label, variable, constante, heka...)
+pub fn reserved(word: &str, role: &str) -> ThotError {
+    ThotError::syntax(format!("'{word}' is a reserved word, rename this {role}"))
+        .with_help("the reserved words are listed in the README")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    // Tous les jetons d'une source, sans le Eof final
+    fn jetons(source: &str) -> ThotResult<Vec<Token>> {
+        let mut lexer = Lexer::new(source, 0);
+        let mut jetons = Vec::new();
+        loop {
+            match lexer.next_token()?.node {
+                Token::Eof => return Ok(jetons),
+                jeton => jetons.push(jeton),
+            }
         }
     }
+
+    fn erreur(source: &str) -> ThotError {
+        jetons(source).expect_err(source)
+    }
+
+    #[test]
+    fn radix_prefixes() {
+        assert_eq!(
+            jetons("0x1F 0XFF 0b1010 0B11 0o17 0O7 42 0").unwrap(),
+            [31, 255, 10, 3, 15, 7, 42, 0].map(Token::Number)
+        );
+    }
+
+    #[test]
+    fn underscore_separators() {
+        assert_eq!(
+            jetons("1_000_000 0b1010_0101 0xdead_beef 0o7_7").unwrap(),
+            [1_000_000, 0b1010_0101, 0xdead_beef, 0o77].map(Token::Number)
+        );
+    }
+
+    #[test]
+    fn helix_channels_up_to_128_bits() {
+        assert_eq!(
+            jetons("340282366920938463463374607431768211455:0x10 5:").unwrap(),
+            [Token::Helix(u128::MAX, 16), Token::Helix(5, 0)]
+        );
+    }
+
+    #[test]
+    fn overflowing_literals() {
+        // 2^128 ne tient dans aucune force de Helix
+        let e = erreur("wab\n  0x1_0000_0000_0000_0000_0000_0000_0000_0000:1");
+        assert_eq!(e.kind, ErrorKind::Overflow);
+        assert!(
+            e.message.contains("does not fit in 128 bits"),
+            "{}",
+            e.message
+        );
+        assert_eq!(e.span, Some(Span::new(0, 2, 3)));
+
+        // Un nombre seul doit tenir dans un i64
+        let e = erreur("sema %hka, 0x8000_0000_0000_0000");
+        assert_eq!(e.kind, ErrorKind::Overflow);
+        assert!(
+            e.message.contains("does not fit in 64 bits"),
+            "{}",
+            e.message
+        );
+        assert_eq!(e.span, Some(Span::new(0, 1, 12)));
+    }
+
+    #[test]
+    fn invalid_digits() {
+        let e = erreur("henek %ka, 0b102");
+        assert_eq!(e.kind, ErrorKind::Lexical);
+        assert!(
+            e.message.contains("Invalid digit '2' in base-2"),
+            "{}",
+            e.message
+        );
+        assert_eq!(e.span, Some(Span::new(0, 1, 12)));
+
+        let e = erreur("\n\t0x");
+        assert!(
+            e.message
+                .contains("Missing digits after the base-16 prefix")
+        );
+        assert_eq!(e.span, Some(Span::new(0, 2, 2)));
+
+        assert!(erreur("01_boot").message.contains("Invalid digit 'b'"));
+    }
+
+    #[test]
+    fn escaped_char_literals() {
+        assert_eq!(
+            jetons(r#"'A' '\n' '\r' '\t' '\0' '\\' '\'' '\"' ' '"#).unwrap(),
+            [65, 10, 13, 9, 0, 92, 39, 34, 32].map(Token::Number)
+        );
+    }
+
+    #[test]
+    fn invalid_char_literals() {
+        let e = erreur(r"henek %ka, '\q'");
+        assert_eq!(e.kind, ErrorKind::Lexical);
+        assert!(
+            e.message
+                .contains(r"Unknown escape in character literal: \q")
+        );
+        assert_eq!(e.span, Some(Span::new(0, 1, 12)));
+
+        let e = erreur("wab\n''");
+        assert!(e.message.contains("Empty character literal"));
+        assert_eq!(e.span, Some(Span::new(0, 2, 1)));
+
+        let e = erreur("  'ab'");
+        assert!(e.message.contains("exactly one character"));
+        assert!(e.help.is_some());
+        assert_eq!(e.span, Some(Span::new(0, 1, 3)));
+
+        assert!(
+            erreur(r"'\")
+                .message
+                .contains("Unterminated character literal")
+        );
+    }
 }
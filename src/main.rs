@@ -1,21 +1,36 @@
 mod ast;
+mod cache;
+mod diagnostic;
 mod elf;
 mod emitter;
+mod error;
+mod eval;
+mod heka;
 mod lexer;
+mod namespace;
 mod parser;
 mod register;
+mod source;
+mod stdlib;
+mod symbols;
 
 use crate::ast::Instruction;
+use crate::cache::Cache;
+use crate::diagnostic::{Diagnostic, peint};
 use crate::elf::Sarcophagus;
 use crate::emitter::Emitter;
-use crate::lexer::Lexer;
+use crate::error::{ThotError, ThotResult};
+use crate::lexer::{Lexer, Token};
 use crate::parser::Parser;
-use clap::{Arg, Command, value_parser};
+use crate::source::{SourceMap, Span, Spanned};
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
 use crossterm::execute;
 use crossterm::style::{Print, Stylize};
 use crossterm::terminal::size;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 fn cli() -> Command {
     Command::new(env!("CARGO_PKG_NAME"))
@@ -31,145 +46,557 @@ fn cli() -> Command {
                 .value_parser(value_parser!(bool))
                 .default_value("false"),
         )
+        .arg(
+            Arg::new("define")
+                .short('D')
+                .value_name("NAME=VALUE")
+                .action(ArgAction::Append)
+                .help("Define a constant before parsing (VALUE defaults to 1)"),
+        )
+        .arg(
+            Arg::new("include")
+                .short('I')
+                .value_name("DIR")
+                .action(ArgAction::Append)
+                .help("Add a directory to the dema search path"),
+        )
+        .arg(
+            Arg::new("dep-file")
+                .long("dep-file")
+                .value_name("FILE")
+                .help("Write a Make rule listing every file the build read"),
+        )
+        .arg(
+            Arg::new("cache")
+                .long("cache")
+                .value_name("DIR")
+                .help("Reuse the previous binary when no file read by the build has changed"),
+        )
+}
+
+// -D NAME=VALUE : une constante injectée dans chaque tablette avant sa lecture
+fn parse_define(define: &str) -> ThotResult<(String, i64)> {
+    let (nom, valeur) = define.split_once('=').unwrap_or((define, "1"));
+    let invalide =
+        |raison: String| ThotError::syntax(format!("Invalid define '-D {define}': {raison}"));
+    let mut lexer = Lexer::new(nom, 0);
+    match (
+        lexer.next_token().map(|t| t.node),
+        lexer.next_token().map(|t| t.node),
+    ) {
+        (Ok(Token::Identifier(_)), Ok(Token::Eof)) => {}
+        _ => return Err(invalide(format!("'{nom}' is not a valid name"))),
+    }
+    let valeur = Parser::new(Lexer::new(valeur, 0))
+        .and_then(|mut parser| parser.parse_constant())
+        .map_err(|e| invalide(e.message))?;
+    Ok((nom.to_string(), valeur))
+}
+
+// Ce que la ligne de commande impose à toutes les tablettes
+pub struct Options {
+    // -D NAME=VALUE
+    defines: HashMap<String, i64>,
+    // Les dossiers de -I, puis ceux de THOT_PATH, dans l'ordre de recherche
+    include_paths: Vec<PathBuf>,
+}
+
+// Ce que le Tisserand a déjà tissé
+pub struct Inclusions {
+    // La chaîne d'inclusion en cours (chemin canonique, chemin affiché, position du dema
+    // qui l'a tissée), tablette principale en tête
+    pile: Vec<(PathBuf, PathBuf, Option<Span>)>,
+    // Chemins canoniques et empreintes blake3 des tablettes déjà tissées, avec leur alias
+    chemins: HashMap<PathBuf, Option<String>>,
+    empreintes: HashMap<blake3::Hash, Option<String>>,
+    // Chaque fichier lu (tablettes et binaires ini) avec l'empreinte des octets lus,
+    // pour le fichier .d et le cache
+    lus: Vec<(PathBuf, blake3::Hash)>,
+}
+
+impl Inclusions {
+    fn new(principal: &Path, code: &str) -> Self {
+        let canonique = fs::canonicalize(principal).unwrap_or(principal.to_path_buf());
+        Inclusions {
+            pile: vec![(canonique.clone(), principal.to_path_buf(), None)],
+            chemins: HashMap::from([(canonique, None)]),
+            empreintes: HashMap::from([(blake3::hash(code.as_bytes()), None)]),
+            lus: vec![(principal.to_path_buf(), blake3::hash(code.as_bytes()))],
+        }
+    }
+
+    fn fichiers(&self) -> Vec<PathBuf> {
+        self.lus.iter().map(|(chemin, _)| chemin.clone()).collect()
+    }
+}
+
+// Les Yeux (Lexer) et l'Esprit (Parser) lisent une tablette entière
+fn lire_tablet(
+    code: &str,
+    file: usize,
+    defines: &HashMap<String, i64>,
+) -> Result<Vec<Spanned<Instruction>>, Vec<ThotError>> {
+    let lexer = Lexer::new(code, file);
+    let mut parser = Parser::new(lexer)?;
+    for (nom, valeur) in defines {
+        parser.define(nom.clone(), *valeur);
+    }
+    parser.parse_tablet()
 }
 
-// Le Tisserand : Il parcourt les instructions et remplace les "dema" par le vrai code
+// Le Tisserand : Il parcourt les instructions et remplace les "dema" par le vrai code.
+// Chaque tablette lue est enregistrée dans `sources` : les positions des instructions
+// tissées pointent donc toujours vers leur tablette d'origine.
 pub fn tiss_tablet(
-    instructions_brutes: Vec<Instruction>,
+    instructions_brutes: Vec<Spanned<Instruction>>,
     dossier_courant: &Path,
-    m: &Path,
-    tablets: &mut Vec<String>,
-) -> Vec<Instruction> {
+    sources: &mut SourceMap,
+    options: &Options,
+    inclusions: &mut Inclusions,
+) -> Result<Vec<Spanned<Instruction>>, Vec<ThotError>> {
     let mut instructions_finales = Vec::new();
+    // Une tablette incluse fautive n'arrête pas la lecture des suivantes
+    let mut erreurs = Vec::new();
     for instruction in instructions_brutes {
-        match instruction {
+        match instruction.node {
             Instruction::Smen { .. } => instructions_finales.push(instruction),
-            Instruction::Dema { path } => {
-                // 1. On trouve le chemin absolu du nouveau fichier
-                let mut chemin_complet = dossier_courant.join(&path);
-                if chemin_complet.extension().is_none() {
-                    chemin_complet.set_extension("maat");
+            Instruction::Dema {
+                path,
+                system,
+                alias,
+            } => {
+                // 1. On trouve le chemin du nouveau fichier (ou la tablette embarquée de std)
+                // dema <nom> désigne toujours une tablette : <a.b> est le fichier a.b.maat
+                let mut nom = PathBuf::from(&path);
+                if system && !path.ends_with(".maat") {
+                    nom = PathBuf::from(format!("{path}.maat"));
+                } else if nom.extension().is_none() {
+                    nom.set_extension("maat");
                 }
-                if chemin_complet == m {
-                    panic!("A tablet cannot include itself");
+                let embarquee = system.then(|| stdlib::tablette(&path)).flatten();
+                let chemin_complet = if embarquee.is_some() {
+                    stdlib::chemin(&path)
+                } else if system && stdlib::is_std(&path) {
+                    erreurs.push(
+                        ThotError::io(format!("The standard library has no tablet '{path}'"))
+                            .at(instruction.span)
+                            .with_help(format!("available: {}", stdlib::noms().join(", "))),
+                    );
+                    continue;
                 } else {
-                    if tablets.contains(&chemin_complet.to_str().expect("").to_string()) {
-                        panic!("A tablet cannot include itself");
+                    match chercher(&nom, dossier_courant, system, options) {
+                        Ok(chemin) => chemin,
+                        Err(essais) => {
+                            erreurs
+                                .push(introuvable("tablet", &path, &essais).at(instruction.span));
+                            continue;
+                        }
+                    }
+                };
+                // Une tablette déjà sur la pile d'inclusion : c'est un cycle
+                let canonique = fs::canonicalize(&chemin_complet).unwrap_or(chemin_complet.clone());
+                if let Some(debut) = inclusions.pile.iter().position(|(c, ..)| *c == canonique) {
+                    let chaine: Vec<String> = inclusions.pile[debut..]
+                        .iter()
+                        .map(|(_, affichage, _)| affichage.display().to_string())
+                        .chain([chemin_complet.display().to_string()])
+                        .collect();
+                    let mut erreur =
+                        ThotError::syntax(format!("Circular dema: {}", chaine.join(" -> ")))
+                            .at(instruction.span)
+                            .with_help(
+                                "a tablet cannot include itself, directly or through others",
+                            );
+                    // Le dema qui a ouvert le cycle, dans la tablette qui revient
+                    if let Some((_, affichage, Some(ouverture))) = inclusions.pile.get(debut + 1) {
+                        erreur = erreur.with_note(
+                            format!(
+                                "the cycle starts when {} is woven here",
+                                affichage.display()
+                            ),
+                            *ouverture,
+                        );
                     }
+                    erreurs.push(erreur);
+                    continue;
                 }
-                tablets.push(chemin_complet.to_str().unwrap().to_string());
-                ok_tablet(
-                    chemin_complet
-                        .file_name()
-                        .expect("")
-                        .to_str()
-                        .expect("")
-                        .replace(".maat", ""),
-                );
-                let code_inclus = fs::read_to_string(&chemin_complet).unwrap_or_else(|_| {
-                    panic!("The Scribe could not find the tablet: {:?}", chemin_complet)
-                });
+                // Inclusion unique : un losange (A -> B, A -> C, B et C -> D) ne tisse D qu'une fois
+                if let Some(deja) = inclusions.chemins.get(&canonique) {
+                    if *deja != alias {
+                        erreurs.push(alias_different(&path, deja).at(instruction.span));
+                    }
+                    continue;
+                }
+                inclusions.chemins.insert(canonique.clone(), alias.clone());
+                let code_inclus = match embarquee {
+                    // Embarquée : elle change avec Thot lui-même, pas avec un fichier à suivre
+                    Some(code) => code.to_string(),
+                    None => {
+                        // Illisible : on le note et on continue, les autres fautes comptent aussi
+                        let code = match fs::read_to_string(&chemin_complet) {
+                            Ok(code) => code,
+                            Err(e) => {
+                                erreurs.push(
+                                    ThotError::io(format!(
+                                        "The Scribe could not read the tablet {}: {e}",
+                                        chemin_complet.display()
+                                    ))
+                                    .at(instruction.span),
+                                );
+                                continue;
+                            }
+                        };
+                        // L'empreinte des octets lus, pas du fichier tel qu'il sera plus tard
+                        let empreinte = blake3::hash(code.as_bytes());
+                        inclusions.lus.push((chemin_complet.clone(), empreinte));
+                        code
+                    }
+                };
+                // La même tablette copiée ailleurs : même contenu, même empreinte
+                let empreinte = blake3::hash(code_inclus.as_bytes());
+                if let Some(deja) = inclusions.empreintes.get(&empreinte) {
+                    if *deja != alias {
+                        erreurs.push(alias_different(&path, deja).at(instruction.span));
+                    }
+                    continue;
+                }
+                inclusions.empreintes.insert(empreinte, alias.clone());
+                if let Err(erreur) = ok_tablet(tablet_name(&chemin_complet)) {
+                    erreurs.push(erreur);
+                }
+                // 2. On enregistre la tablette pour que ses positions restent retrouvables
+                let file = sources.add(&chemin_complet, code_inclus.clone());
 
                 // 3. On relance les Yeux et l'Esprit sur ce nouveau texte
-                let lexer = Lexer::new(&code_inclus);
-                let mut parser = Parser::new(lexer);
-
-                let mut sous_instructions = Vec::new();
-                while parser.not_eof() {
-                    sous_instructions.push(parser.parse_instruction());
+                let mut sous_instructions = match lire_tablet(&code_inclus, file, &options.defines)
+                {
+                    Ok(sous_instructions) => sous_instructions,
+                    Err(sous_erreurs) => {
+                        erreurs.extend(sous_erreurs);
+                        continue;
+                    }
+                };
+                // Ses symboles privés se cachent, les autres prennent l'alias éventuel
+                let tablette = format!("{}{file}", tablet_name(&chemin_complet));
+                if let Err(sous_erreurs) =
+                    namespace::isoler(&mut sous_instructions, alias.as_deref(), &tablette)
+                {
+                    erreurs.extend(sous_erreurs);
+                    continue;
                 }
 
                 // 4. RÉCURSION : On tisse ce nouveau fichier au cas où IL contienne aussi des 'dema' !
                 let dossier_parent = chemin_complet.parent().unwrap_or(Path::new(""));
-                let sous_instructions_tissees =
-                    tiss_tablet(sous_instructions, dossier_parent, m, tablets);
-
-                // 5. On fusionne les instructions tissées dans notre ligne temporelle principale
-                instructions_finales.extend(sous_instructions_tissees);
+                inclusions
+                    .pile
+                    .push((canonique, chemin_complet.clone(), Some(instruction.span)));
+                let tissage = tiss_tablet(
+                    sous_instructions,
+                    dossier_parent,
+                    sources,
+                    options,
+                    inclusions,
+                );
+                inclusions.pile.pop();
+                match tissage {
+                    // 5. On fusionne les instructions tissées dans notre ligne temporelle principale
+                    Ok(sous_instructions_tissees) => {
+                        instructions_finales.extend(sous_instructions_tissees)
+                    }
+                    Err(sous_erreurs) => erreurs.extend(sous_erreurs),
+                }
+            }
+            // Un fichier binaire se cherche comme une tablette : relatif à celle qui l'inclut
+            Instruction::Ini { path } => {
+                let chemin_complet =
+                    match chercher(Path::new(&path), dossier_courant, false, options) {
+                        Ok(chemin) => chemin,
+                        Err(essais) => {
+                            erreurs.push(
+                                introuvable("binary file", &path, &essais).at(instruction.span),
+                            );
+                            continue;
+                        }
+                    };
+                match fs::read(&chemin_complet) {
+                    Ok(octets) => {
+                        let empreinte = blake3::hash(&octets);
+                        inclusions.lus.push((chemin_complet, empreinte));
+                        instructions_finales
+                            .push(Spanned::new(Instruction::Octets(octets), instruction.span))
+                    }
+                    Err(e) => erreurs.push(
+                        ThotError::io(format!(
+                            "The Scribe could not read the binary file {}: {e}",
+                            chemin_complet.display()
+                        ))
+                        .at(instruction.span),
+                    ),
+                }
             }
             // Si c'est une instruction normale, ont la garde intacte
-            autre => instructions_finales.push(autre),
+            autre => instructions_finales.push(Spanned::new(autre, instruction.span)),
         }
     }
-    instructions_finales
+    if erreurs.is_empty() {
+        Ok(instructions_finales)
+    } else {
+        Err(erreurs)
+    }
 }
 
-fn ok_tablet(tablet: String) {
-    let (w, _) = size().expect("Failed to get size");
-    let description = "Tablet has been compiled successfully";
+// Trouve le fichier d'un dema ou d'un ini : à côté de la tablette qui l'inclut (sauf pour
+// dema <nom>), puis dans les dossiers -I et THOT_PATH. En cas d'échec, rend les chemins essayés.
+fn chercher(
+    path: &Path,
+    dossier_courant: &Path,
+    system: bool,
+    options: &Options,
+) -> Result<PathBuf, Vec<PathBuf>> {
+    let candidats: Vec<PathBuf> = (!system)
+        .then_some(dossier_courant)
+        .into_iter()
+        .chain(options.include_paths.iter().map(PathBuf::as_path))
+        .map(|dossier| dossier.join(path))
+        .collect();
+    candidats
+        .iter()
+        .find(|candidat| candidat.is_file())
+        .cloned()
+        .ok_or(candidats)
+}
+
+fn introuvable(quoi: &str, path: &str, essais: &[PathBuf]) -> ThotError {
+    let erreur = ThotError::io(format!("The Scribe could not find the {quoi} '{path}'"));
+    if essais.is_empty() {
+        erreur.with_help("no system directory is set: add one with -I or THOT_PATH")
+    } else {
+        let essais: Vec<String> = essais.iter().map(|e| e.display().to_string()).collect();
+        erreur.with_help(format!("searched {}", essais.join(", ")))
+    }
+}
+
+// Une tablette déjà tissée ne change pas de nom : elle n'existe qu'une fois dans le binaire
+fn alias_different(path: &str, deja: &Option<String>) -> ThotError {
+    let avant = match deja {
+        Some(alias) => format!("as '{alias}'"),
+        None => "without a namespace".to_string(),
+    };
+    ThotError::syntax(format!("The tablet '{path}' is already woven {avant}"))
+        .with_help("import a tablet under a single name everywhere")
+}
+
+fn tablet_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn ok_tablet(tablet: String) -> ThotResult<()> {
+    status_line(tablet, "has been compiled successfully")
+}
+
+fn status_line(tablet: String, etat: &str) -> ThotResult<()> {
+    // Sans terminal (pipe, CI), on se contente d'une largeur classique
+    let (w, _) = size().unwrap_or((80, 24));
+    let description = format!("Tablet {etat}");
     let x = "* ".to_string();
     let cr = " [ ".to_string();
     let cl = " ] ".to_string();
     let status = "ok".to_string();
     let y = tablet.to_string();
     let padding = w
-        - y.chars().count() as u16
-        - cr.chars().count() as u16
-        - cl.chars().count() as u16
-        - status.chars().count() as u16
-        - description.chars().count() as u16
-        - x.chars().count() as u16
-        - 5;
+        .saturating_sub(y.chars().count() as u16)
+        .saturating_sub(cr.chars().count() as u16)
+        .saturating_sub(cl.chars().count() as u16)
+        .saturating_sub(status.chars().count() as u16)
+        .saturating_sub(description.chars().count() as u16)
+        .saturating_sub(x.chars().count() as u16)
+        .saturating_sub(5);
     execute!(
         std::io::stdout(),
         Print(x.green().bold()),
-        Print(format!(
-            "The tablet {} has been compiled successfully",
-            y.green().bold()
-        )),
+        Print(format!("The tablet {} {etat}", y.green().bold())),
         Print(" ".repeat(padding as usize)),
         Print(cr.white().bold()),
         Print(status.green().bold()),
         Print(cl.white().bold()),
         Print("\n")
     )
-    .unwrap();
+    .map_err(|e| ThotError::io(format!("Failed to write the status line: {e}")))
 }
 
-fn main() {
-    let matches = cli().get_matches();
-    let mut tablets = Vec::new();
+// Les options qui changent le binaire sans passer par un fichier lu
+fn cache_options(boot: bool, options: &Options) -> Vec<String> {
+    let mut defines: Vec<String> = options
+        .defines
+        .iter()
+        .map(|(nom, valeur)| format!("-D{nom}={valeur}"))
+        .collect();
+    defines.sort();
+    [
+        format!("boot={boot}"),
+        format!("std={}", stdlib::empreinte()),
+    ]
+    .into_iter()
+    .chain(defines)
+    .chain(
+        options
+            .include_paths
+            .iter()
+            .map(|dossier| format!("-I{}", dossier.display())),
+    )
+    .collect()
+}
+
+fn write_output(out: &str, binary: &[u8]) -> ThotResult<()> {
+    fs::write(out, binary).map_err(|e| ThotError::io(format!("Failed to write {out}: {e}")))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(out, fs::Permissions::from_mode(0o755))
+            .map_err(|e| ThotError::io(format!("Failed to set permissions on {out}: {e}")))?;
+    }
+    Ok(())
+}
 
+fn compile(matches: &ArgMatches, sources: &mut SourceMap) -> Result<(), Vec<ThotError>> {
     // On utilise if let imbriqués (plus stable sur toutes les versions de Rust)
     if let Some(file) = matches.get_one::<String>("maat")
         && let Some(out) = matches.get_one::<String>("output")
     {
-        let code_source = fs::read_to_string(file)
-            .expect("Erreur fatale : Le Scribe n'a pas pu lire le fichier source principal.");
+        let code_source = fs::read_to_string(file).map_err(|e| {
+            ThotError::io(format!(
+                "Le Scribe n'a pas pu lire le fichier source principal {file}: {e}"
+            ))
+        })?;
 
-        // 2. Les Yeux (Lexer) et l'Esprit (Parser) analysent le texte
-        let lexer = Lexer::new(&code_source);
-        let mut parser = Parser::new(lexer);
+        let main_file = sources.add(Path::new(file.as_str()), code_source.clone());
 
+        // 2. Les Yeux (Lexer) et l'Esprit (Parser) analysent le texte
         // 3. On remplit le vecteur avec les vraies instructions du fichier
-        let mut instructions = Vec::new();
-        while parser.not_eof() {
-            instructions.push(parser.parse_instruction());
+        let mut defines = matches
+            .get_many::<String>("define")
+            .into_iter()
+            .flatten()
+            .map(|define| parse_define(define))
+            .collect::<ThotResult<HashMap<_, _>>>()?;
+        let include_paths = matches
+            .get_many::<String>("include")
+            .into_iter()
+            .flatten()
+            .map(PathBuf::from)
+            .chain(
+                std::env::var_os("THOT_PATH")
+                    .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+                    .unwrap_or_default(),
+            )
+            .filter(|dossier| !dossier.as_os_str().is_empty())
+            .collect();
+        // La version de la bibliothèque standard, sauf si -D en impose une autre
+        defines
+            .entry("THOT_STD_VERSION".to_string())
+            .or_insert(stdlib::VERSION);
+        let options = Options {
+            defines,
+            include_paths,
+        };
+        let chemin_fichier_principal = Path::new(file);
+        let boot = matches.get_flag("boot");
+
+        // Rien n'a changé depuis la dernière compilation : le binaire du cache suffit
+        let cache = matches.get_one::<String>("cache").and_then(|dossier| {
+            Cache::new(
+                Path::new(dossier),
+                chemin_fichier_principal,
+                &cache_options(boot, &options),
+            )
+        });
+        if let Some((binary, lus)) = cache.as_ref().and_then(Cache::lire) {
+            write_output(out, &binary)?;
+            if let Some(depfile) = matches.get_one::<String>("dep-file") {
+                cache::write_depfile(Path::new(depfile), out, &lus)?;
+            }
+            status_line(tablet_name(chemin_fichier_principal), "is up to date")?;
+            return Ok(());
         }
+
+        let instructions = lire_tablet(&code_source, main_file, &options.defines)?;
+
         // On récupère le dossier du fichier principal pour gérer les chemins relatifs
-        let chemin_fichier_principal = Path::new(file);
         let dossier_principal = chemin_fichier_principal.parent().unwrap_or(Path::new(""));
         // On aplatit l'arbre syntaxique en résolvant toutes les inclusions
-        let instructions_fusionnees =
-            tiss_tablet(instructions, dossier_principal, Path::new(file.as_str()),&mut tablets);
+        let mut inclusions = Inclusions::new(chemin_fichier_principal, &code_source);
+        let instructions_fusionnees = tiss_tablet(
+            instructions,
+            dossier_principal,
+            sources,
+            &options,
+            &mut inclusions,
+        )?;
+
+        // Les heka se déplient sur le programme aplati : une tablette incluse peut les définir
+        let mut instructions_fusionnees = heka::expand(instructions_fusionnees)?;
+
+        // Un symbole ne se définit qu'une fois, et jamais sous le nom d'une routine de Thot
+        symbols::check_definitions(&instructions_fusionnees)?;
 
-        let bin = Emitter::new()
-            .add_instruction(instructions_fusionnees.clone())
+        // Les constantes smen valent partout, même avant leur définition
+        symbols::resolve_constants(&mut instructions_fusionnees)?;
+
+        let mut emitter = Emitter::new();
+        let resultat = emitter
+            .add_instruction(instructions_fusionnees)
             .set_kbd_layout(String::from("qwerty"))
             .generer_binaire(true);
+        // Les avertissements s'affichent même si la génération échoue
+        for warning in emitter.warnings() {
+            render(warning, sources);
+        }
+        let bin = resultat?;
 
-        let binary = if matches.get_flag("boot") {
+        let binary = if boot {
             bin
         } else {
             Sarcophagus::packaging(&bin)
         };
-        fs::write(out, binary).expect("Failed to write bin");
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(out, fs::Permissions::from_mode(0o755))
-                .expect("Failed to set permissions");
+        write_output(out, &binary)?;
+        if let Some(depfile) = matches.get_one::<String>("dep-file") {
+            cache::write_depfile(Path::new(depfile), out, &inclusions.fichiers())?;
+        }
+        if let Some(cache) = &cache {
+            cache.ecrire(&binary, &inclusions.lus)?;
         }
-        ok_tablet(file.replace(".maat", ""));
+        ok_tablet(tablet_name(chemin_fichier_principal))?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    let mut sources = SourceMap::new();
+
+    match compile(&matches, &mut sources) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(erreurs) => {
+            for erreur in &erreurs {
+                render(&Diagnostic::from(erreur), &sources);
+            }
+            if erreurs.len() > 1 {
+                eprintln!(
+                    "{}: aborting due to {} previous errors",
+                    peint("error".red().bold()),
+                    erreurs.len()
+                );
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn render(diagnostic: &Diagnostic, sources: &SourceMap) {
+    // Si le terminal refuse les couleurs, on garde au moins le message brut
+    if diagnostic.render(sources).is_err() {
+        eprintln!("{}", diagnostic.message);
     }
 }
@@ -0,0 +1,145 @@
+use crate::ast::{Expression, Instruction};
+use crate::error::ThotError;
+use crate::source::Spanned;
+use std::collections::{HashMap, HashSet};
+
+/// Isole les symboles d'une tablette incluse, avant que ses propres `dema` soient tissés :
+/// - `private nom` cache `nom` (et ses labels locaux `nom.x`) aux tablettes qui l'importent ;
+/// - dès qu'un `export` apparaît, tout ce qui n'est pas exporté est privé ;
+/// - avec `dema "..." as ns`, les symboles visibles deviennent `ns.nom`.
+///
+/// Les marqueurs `export`/`private` sont consommés. `tablette` rend les noms cachés uniques.
+pub fn isoler(
+    instructions: &mut Vec<Spanned<Instruction>>,
+    alias: Option<&str>,
+    tablette: &str,
+) -> Result<(), Vec<ThotError>> {
+    // 1. Ce que la tablette définit elle-même
+    let mut definis = HashSet::new();
+    for instruction in instructions.iter() {
+        if let Some(nom) = definition(&instruction.node) {
+            definis.insert(nom.to_string());
+        }
+    }
+
+    // 2. Les marqueurs, vérifiés puis retirés
+    let mut exportes = HashSet::new();
+    let mut prives = HashSet::new();
+    let mut erreurs = Vec::new();
+    instructions.retain(|instruction| {
+        let (noms, cible, verbe) = match &instruction.node {
+            Instruction::Export(noms) => (noms, &mut exportes, "export"),
+            Instruction::Private(noms) => (noms, &mut prives, "private"),
+            _ => return true,
+        };
+        for nom in noms {
+            if definis.contains(nom) {
+                cible.insert(nom.clone());
+            } else {
+                erreurs.push(
+                    ThotError::syntax(format!("'{verbe} {nom}': no such symbol in this tablet"))
+                        .at(instruction.span)
+                        .with_help("only labels, nama, smen and heka defined here can be marked"),
+                );
+            }
+        }
+        false
+    });
+    if let Some(nom) = exportes.iter().find(|nom| prives.contains(*nom)) {
+        erreurs.push(ThotError::syntax(format!(
+            "'{nom}' is marked both export and private in tablet '{tablette}'"
+        )));
+    }
+    if !erreurs.is_empty() {
+        return Err(erreurs);
+    }
+
+    // 3. Le nouveau nom de chaque symbole. Un label local suit le sort de son label global.
+    let marque = |ensemble: &HashSet<String>, nom: &str| {
+        ensemble.contains(nom)
+            || nom
+                .split_once('.')
+                .is_some_and(|(global, _)| ensemble.contains(global))
+    };
+    let mut table = HashMap::new();
+    for nom in definis {
+        let prive = marque(&prives, &nom) || (!exportes.is_empty() && !marque(&exportes, &nom));
+        let nouveau = if prive {
+            // '@' n'existe pas dans un identifiant Maât : personne ne peut l'écrire ailleurs
+            format!("{nom}@{tablette}")
+        } else if let Some(alias) = alias {
+            format!("{alias}.{nom}")
+        } else {
+            continue;
+        };
+        table.insert(nom, nouveau);
+    }
+
+    // 4. Définitions et références prennent le nouveau nom
+    if !table.is_empty() {
+        for instruction in instructions.iter_mut() {
+            renommer(&mut instruction.node, &table);
+        }
+    }
+    Ok(())
+}
+
+// Le symbole qu'une instruction définit au niveau de la tablette
+fn definition(instruction: &Instruction) -> Option<&str> {
+    match instruction {
+        Instruction::Label(nom) => Some(nom),
+        Instruction::Nama { name, .. } => Some(name),
+        Instruction::Smen { nom, .. } => Some(nom),
+        Instruction::Heka { nom, .. } => Some(nom),
+        _ => None,
+    }
+}
+
+fn renommer(instruction: &mut Instruction, table: &HashMap<String, String>) {
+    let nouveau = |nom: &mut String| {
+        if let Some(n) = table.get(nom.as_str()) {
+            *nom = n.clone();
+        }
+    };
+    match instruction {
+        Instruction::Label(nom)
+        | Instruction::Nama { name: nom, .. }
+        | Instruction::Smen { nom, .. }
+        | Instruction::Invocation { nom, .. } => nouveau(nom),
+        Instruction::Heka {
+            nom,
+            parametres,
+            corps,
+        } => {
+            nouveau(nom);
+            // Dans le corps, un paramètre masque le symbole du même nom
+            let mut table = table.clone();
+            table.retain(|nom, _| !parametres.contains(nom));
+            for instruction in corps {
+                renommer(&mut instruction.node, &table);
+            }
+            return;
+        }
+        _ => {}
+    }
+    for expression in instruction.expressions_mut() {
+        renommer_expression(expression, table);
+    }
+}
+
+fn renommer_expression(expression: &mut Expression, table: &HashMap<String, String>) {
+    match expression {
+        Expression::Identifier(nom) => {
+            if let Some(nouveau) = table.get(nom.as_str()) {
+                *nom = nouveau.clone();
+            }
+        }
+        Expression::Binary { left, right, .. } => {
+            renommer_expression(left, table);
+            renommer_expression(right, table);
+        }
+        Expression::Unary { operand, .. } => renommer_expression(operand, table),
+        Expression::Memory(adresse) => renommer_expression(&mut adresse.displacement, table),
+        _ => {}
+    }
+}
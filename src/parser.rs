@@ -1,25 +1,54 @@
-use crate::ast::{Expression, Instruction, Level};
-use crate::lexer::{Lexer, Token};
+use crate::ast::{Address, Expression, Instruction, Level};
+use crate::error::{ThotError, ThotResult};
+use crate::eval::{BinaryOp, UnaryOp, identifiers};
+use crate::lexer::{Lexer, Token, reserved};
 use crate::register::{
-    RegBase, RegKind, ensure_helix_fits, ensure_number_fits, ensure_same_level,
-    parse_general_register, parse_register,
+    RegBase, RegKind, ensure_division, ensure_helix_fits, ensure_helix_level, ensure_number_fits,
+    ensure_same_level, ensure_shift_count, parse_general_register, parse_register,
 };
+use crate::source::{Span, Spanned};
 
 #[derive(Clone)]
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
     current_token: Token,
-    constant: std::collections::HashMap<String, i32>,
+    current_span: Span,
+    constant: std::collections::HashMap<String, i64>,
+    // Les blocs 'if' ouverts dont la branche retenue est en cours de lecture
+    conditions: Vec<Span>,
+    // Le dernier label global : les labels locaux ('.boucle') lui appartiennent
+    scope: Option<String>,
 }
 
 impl<'a> Parser<'a> {
     // Initialise le Parser et charge le premier jeton
-    pub fn new(mut lexer: Lexer<'a>) -> Self {
-        let first_token = lexer.next_token();
-        Parser {
+    pub fn new(mut lexer: Lexer<'a>) -> ThotResult<Self> {
+        let first_token = lexer.next_token()?;
+        Ok(Parser {
             lexer,
-            current_token: first_token,
+            current_token: first_token.node,
+            current_span: first_token.span,
             constant: std::collections::HashMap::new(),
+            conditions: Vec::new(),
+            scope: None,
+        })
+    }
+    // '.boucle' appartient au dernier label global 'attendre' : il devient 'attendre.boucle'
+    fn qualify(&self, nom: String) -> String {
+        match &self.scope {
+            Some(global) if nom.starts_with('.') => format!("{global}{nom}"),
+            _ => nom,
+        }
+    }
+    // Une constante venue d'ailleurs (-D NAME=VALUE), connue avant la première ligne
+    pub fn define(&mut self, nom: String, valeur: i64) {
+        self.constant.insert(nom, valeur);
+    }
+    // Une expression seule, sans instruction : la valeur d'un -D NAME=VALUE
+    pub fn parse_constant(&mut self) -> ThotResult<i64> {
+        match self.parse_expression()? {
+            Expression::Number(n) if self.eof() => Ok(n),
+            _ => Err(self.unexpected("Expected a single constant value")),
         }
     }
     pub fn current_token(&self) -> Token {
@@ -32,163 +61,440 @@ impl<'a> Parser<'a> {
         !self.eof()
     }
     // Passe au jeton suivant
-    fn advance(&mut self) {
-        self.current_token = self.lexer.next_token();
+    fn advance(&mut self) -> ThotResult<()> {
+        let next = self.lexer.next_token()?;
+        self.current_token = next.node;
+        self.current_span = next.span;
+        Ok(())
+    }
+
+    // Une erreur de syntaxe pointée sur le jeton courant
+    fn unexpected(&self, message: impl Into<String>) -> ThotError {
+        ThotError::syntax(message).at(self.current_span)
+    }
+
+    // Un mot réservé là où un nom est attendu : on l'avale, sinon la resynchronisation
+    // le prendrait pour le verbe de l'instruction suivante
+    fn reserved_name(&mut self, role: &str) -> ThotError {
+        let erreur = match &self.current_token {
+            Token::Verb(mot) => reserved(mot, role).at(self.current_span),
+            autre => self.unexpected(format!("Expected a {role}, found {autre:?}")),
+        };
+        // Le mot réservé est la vraie faute : une erreur du Lexer juste après passe après lui
+        let _ = self.advance();
+        erreur
     }
 
     // Vérifie qu'on a le bon jeton, sinon le compilateur hurle (Erreur de syntaxe)
-    fn expect_token(&mut self, expected: Token) {
+    fn expect_token(&mut self, expected: Token) -> ThotResult<()> {
         if self.current_token == expected {
-            self.advance();
+            self.advance()
         } else {
-            panic!(
-                "Syntax Error: Expected {:?}, but found {:?}",
+            Err(self.unexpected(format!(
+                "Expected {:?}, but found {:?}",
                 expected, self.current_token
-            );
+            )))
         }
     }
-    // Niveau 1 : Gère l'addition et la soustraction (Les opérations "lentes")
-    fn parse_expression(&mut self) -> Expression {
-        let mut gauche = match self.current_token.clone() {
-            Token::Number(n) => {
-                self.advance();
-                Expression::Number(n)
+    // Une expression complète : constantes repliées à la compilation, avec
+    // parenthèses et priorités (de la plus faible à la plus forte) :
+    //   == != < <= > >=   |   ^   &   << >>   + -   * / %   puis unaires - ~
+    fn parse_expression(&mut self) -> ThotResult<Expression> {
+        self.parse_binaire(0)
+    }
+
+    // Remontée de priorités : on ne consomme que les opérateurs au moins aussi forts que `min`
+    fn parse_binaire(&mut self, min: u8) -> ThotResult<Expression> {
+        let mut gauche = self.parse_unaire()?;
+        while let Some(op) = BinaryOp::from_token(&self.current_token) {
+            if op.precedence() < min {
+                break;
             }
-            Token::Helix(ra, apophis) => {
-                self.advance();
-                Expression::Helix { ra, apophis }
+            let span = self.current_span;
+            self.advance()?;
+            // Associativité à gauche : la droite doit lier strictement plus fort
+            let droite = self.parse_binaire(op.precedence() + 1)?;
+            gauche = combiner(op, gauche, droite).map_err(|e| e.at(span))?;
+        }
+        Ok(gauche)
+    }
+
+    // Un opérande mémoire, crochets compris : [base + index*échelle ± déplacement].
+    // Les registres ne peuvent qu'être ajoutés ; tout le reste forme le déplacement.
+    fn parse_adresse(&mut self) -> ThotResult<Expression> {
+        self.expect_token(Token::OpenBracket)?;
+        let mut base: Option<String> = None;
+        let mut index: Option<(String, u8)> = None;
+        let mut deplacement = Expression::Number(0);
+        let mut signe = BinaryOp::Add;
+        loop {
+            let span = self.current_span;
+            if let Token::Register(r) = self.current_token.clone() {
+                if signe == BinaryOp::Sub {
+                    return Err(ThotError::syntax(format!(
+                        "A register cannot be subtracted in an address: '- %{r}'"
+                    ))
+                    .at(span));
+                }
+                let _ = parse_general_register(&r)?;
+                self.advance()?;
+                if self.current_token == Token::Star {
+                    self.advance()?; // Mange '*'
+                    let echelle = match self.current_token {
+                        Token::Number(n @ (1 | 2 | 4 | 8)) => n as u8,
+                        _ => {
+                            return Err(self.unexpected(format!(
+                                "Index scale must be 1, 2, 4 or 8, found {:?}",
+                                self.current_token
+                            )));
+                        }
+                    };
+                    self.advance()?;
+                    if index.is_some() {
+                        return Err(ThotError::syntax(
+                            "An address can only have one scaled index register",
+                        )
+                        .at(span));
+                    }
+                    index = Some((r, echelle));
+                } else if base.is_none() {
+                    base = Some(r);
+                } else if index.is_none() {
+                    index = Some((r, 1));
+                } else {
+                    return Err(ThotError::syntax(
+                        "An address uses at most two registers (base and index)",
+                    )
+                    .at(span));
+                }
+            } else {
+                // Un terme du déplacement : on s'arrête avant le prochain '+' ou '-'
+                let terme = self.parse_binaire(BinaryOp::Mul.precedence())?;
+                deplacement = match (deplacement, signe) {
+                    (Expression::Number(0), BinaryOp::Add) => terme,
+                    (d, op) => combiner(op, d, terme).map_err(|e| e.at(span))?,
+                };
             }
+            signe = match self.current_token {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Sub,
+                _ => break,
+            };
+            self.advance()?;
+        }
+        self.expect_token(Token::CloseBracket)?; // Mange ']'
+        Ok(Expression::Memory(Box::new(Address {
+            base,
+            index,
+            displacement: deplacement,
+        })))
+    }
+
+    fn parse_unaire(&mut self) -> ThotResult<Expression> {
+        let op = match self.current_token {
+            Token::Minus => UnaryOp::Neg,
+            Token::Tilde => UnaryOp::Not,
+            _ => return self.parse_primaire(),
+        };
+        let span = self.current_span;
+        self.advance()?;
+        match self.parse_unaire()? {
+            Expression::Number(n) => Ok(Expression::Number(op.apply(n).map_err(|e| e.at(span))?)),
+            operande if operande.is_relocatable() => Ok(Expression::Unary {
+                op,
+                operand: Box::new(operande),
+            }),
+            _ => Err(
+                ThotError::syntax(format!("The '{op}' sign must be followed by a number."))
+                    .at(span),
+            ),
+        }
+    }
+
+    fn parse_primaire(&mut self) -> ThotResult<Expression> {
+        let expression = match self.current_token.clone() {
+            Token::Number(n) => Expression::Number(n),
+            Token::Helix(ra, apophis) => Expression::Helix { ra, apophis },
             Token::Register(r) => {
-                let _ = parse_register(&r);
-                self.advance();
+                let _ = parse_register(&r)?;
                 Expression::Register(r)
             }
             Token::Identifier(i) => {
-                self.advance();
                 // Le Scribe doit vérifier si ce nom est une constante connue !
                 if let Some(&valeur) = self.constant.get(&i) {
                     Expression::Number(valeur) // On substitue le nom par sa valeur
                 } else {
-                    Expression::Identifier(i) // Sinon, on garde le nom (c'est un label)
+                    Expression::Identifier(self.qualify(i)) // Sinon, on garde le nom (c'est un label)
                 }
             }
-            Token::StringLiteral(s) => {
-                self.advance();
-                Expression::StringLiteral(s)
+            Token::StringLiteral(s) => Expression::StringLiteral(s),
+            Token::Dollar => Expression::CurrentAddress,
+            Token::OpenParen => {
+                self.advance()?; // On consomme le '('
+                let interieur = self.parse_expression()?;
+                if self.current_token != Token::CloseParen {
+                    return Err(self.unexpected(format!(
+                        "Expected ')' to close the expression, found {:?}",
+                        self.current_token
+                    )));
+                }
+                interieur
             }
-            // --- LA PIÈCE MANQUANTE ---
-            Token::Dollar => {
-                self.advance(); // On consomme le '$'
-                Expression::CurrentAddress
+            Token::Verb(_) => return Err(self.reserved_name("name")),
+            _ => {
+                return Err(self.unexpected(format!(
+                    "Expression expected, found {:?}",
+                    self.current_token
+                )));
             }
-            Token::Minus => {
-                self.advance(); // On consomme le '-'
-                if let Token::Number(n) = self.current_token {
-                    let valeur_negative = -n;
-                    self.advance();
-                    Expression::Number(valeur_negative)
+        };
+        self.advance()?;
+        Ok(expression)
+    }
+
+    // Lit toute la tablette. Après une erreur, l'Esprit se resynchronise au lieu
+    // d'abandonner : toutes les erreurs de la tablette sont rapportées en un seul passage.
+    pub fn parse_tablet(&mut self) -> Result<Vec<Spanned<Instruction>>, Vec<ThotError>> {
+        let mut instructions = Vec::new();
+        let mut erreurs = Vec::new();
+        while self.not_eof() {
+            let debut = self.current_span;
+            // Les directives if/elif/else/end choisissent les lignes que l'Esprit va lire
+            let instruction = self.parse_conditions(0).and_then(|()| {
+                if self.eof() {
+                    Ok(None)
                 } else {
-                    panic!("Error: The '-' sign must be followed by a number.");
+                    self.parse_instruction().map(Some)
+                }
+            });
+            match instruction {
+                Ok(Some(instruction)) => instructions.push(instruction),
+                Ok(None) => {}
+                Err(erreur) => {
+                    erreurs.push(erreur);
+                    self.synchronize(debut, &mut erreurs);
                 }
             }
-            _ => panic!(
-                "Syntax Error: Expression expected, found {:?}",
-                self.current_token
-            ),
-        };
-        while matches!(self.current_token, Token::Plus | Token::Minus) {
-            let operateur = self.current_token.clone();
-            self.advance();
-            let droite = self.parse_terme(); // Priorité aux multiplications à droite aussi
+        }
+        for debut in self.conditions.drain(..) {
+            erreurs.push(
+                ThotError::syntax("'if' is never closed")
+                    .at(debut)
+                    .with_help("close the block with 'end'"),
+            );
+        }
+        if erreurs.is_empty() {
+            Ok(instructions)
+        } else {
+            Err(erreurs)
+        }
+    }
+
+    // Saute les jetons jusqu'à la ligne suivante ou au prochain verbe connu.
+    // On avance toujours d'au moins un jeton pour ne pas rester bloqué sur la faute.
+    fn synchronize(&mut self, debut: Span, erreurs: &mut Vec<ThotError>) {
+        loop {
+            let avance = self.current_span != debut;
+            let frontiere =
+                self.current_span.line > debut.line || matches!(self.current_token, Token::Verb(_));
+            if self.eof() || (avance && frontiere) {
+                return;
+            }
+            // Le Lexer a déjà consommé le caractère fautif : on peut continuer
+            if let Err(erreur) = self.advance() {
+                erreurs.push(erreur);
+            }
+        }
+    }
 
-            if let (Expression::Number(n1), Expression::Number(n2)) = (&gauche, &droite) {
-                gauche = match operateur {
-                    Token::Plus => Expression::Number(n1 + n2),
-                    Token::Minus => Expression::Number(n1 - n2),
-                    _ => unreachable!(),
+    // Compilation conditionnelle : traite les if/elif/else/end à la position courante.
+    // Les conditions sont évaluées dès la lecture ; une branche écartée n'est jamais analysée.
+    // `plancher` protège les blocs ouverts hors de la heka en cours de lecture.
+    fn parse_conditions(&mut self, plancher: usize) -> ThotResult<()> {
+        loop {
+            match self.current_token() {
+                Token::Verb(v) if v == "if" => {
+                    let debut = self.current_span;
+                    self.advance()?; // Consomme 'if'
+                    match self.condition() {
+                        Ok(true) => self.conditions.push(debut),
+                        Ok(false) => self.skip_branch(debut, true)?,
+                        Err(erreur) => {
+                            // Le bloc entier est écarté pour ne pas voir de 'end' orphelin
+                            let _ = self.skip_branch(debut, false);
+                            return Err(erreur);
+                        }
+                    }
+                }
+                // La branche retenue s'achève : les suivantes sont sautées jusqu'au 'end'
+                Token::Verb(v)
+                    if (v == "elif" || v == "else") && self.conditions.len() > plancher =>
+                {
+                    let debut = self.conditions.pop().unwrap_or(self.current_span);
+                    self.advance()?;
+                    self.skip_branch(debut, false)?;
+                }
+                Token::Verb(v) if v == "end" && self.conditions.len() > plancher => {
+                    self.conditions.pop();
+                    self.advance()?; // Consomme 'end'
+                }
+                Token::Verb(v) if v == "elif" || v == "else" => {
+                    let erreur = self.unexpected(format!("'{v}' without an opening 'if'"));
+                    self.advance()?; // Consomme le mot orphelin pour repartir après lui
+                    return Err(erreur);
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    // La condition d'un if/elif : elle doit être connue dès la lecture
+    fn condition(&mut self) -> ThotResult<bool> {
+        let span = self.current_span;
+        match self.parse_expression()? {
+            Expression::Number(n) => Ok(n != 0),
+            autre => {
+                let message = match identifiers(&autre).first() {
+                    Some(nom) => format!("'{nom}' is not a constant known at this point"),
+                    None => "The condition of 'if' must be a constant".to_string(),
                 };
-            } else {
-                panic!("Isfet: Thot only solves constants at the moment.");
+                Err(ThotError::syntax(message)
+                    .at(span)
+                    .with_help("use a number, a smen defined above, or -D NAME=VALUE"))
             }
         }
-        gauche
-    }
-
-    // Niveau 2 : Gère la multiplication et la division (Les opérations "rapides")
-    fn parse_terme(&mut self) -> Expression {
-        let mut gauche = match &self.current_token {
-            Token::Minus => {
-                // Gestion du nombre négatif
-                self.advance();
-                match &self.current_token {
-                    Token::Number(n) => Expression::Number(-(*n)),
-                    _ => panic!("Syntax Error: '-' expects a number"),
-                }
-            }
-            Token::Helix(ra, apophis) => Expression::Helix {
-                ra: *ra,
-                apophis: *apophis,
-            },
-            // 3. Dans parse_terme ou parse_expression, substitue les noms par leur valeur
-            Token::Identifier(nom) => {
-                if let Some(&val) = self.constant.get(nom) {
-                    Expression::Number(val) // Si c'est une constante smen, on renvoie son nombre !
-                } else {
-                    Expression::Identifier(nom.clone()) // Sinon c'est une variable nama
+    }
+
+    // Saute une branche écartée ; les blocs imbriqués (if, heka) sont sautés en entier.
+    // Avec `chercher`, s'arrête sur la première branche retenue (elif vrai, else),
+    // sinon va jusqu'au 'end' du bloc.
+    fn skip_branch(&mut self, debut: Span, chercher: bool) -> ThotResult<()> {
+        let mut profondeur = 0;
+        loop {
+            match self.current_token() {
+                Token::Eof => {
+                    return Err(ThotError::syntax("'if' is never closed")
+                        .at(debut)
+                        .with_help("close the block with 'end'"));
+                }
+                Token::Verb(v) if v == "if" || v == "heka" => profondeur += 1,
+                Token::Verb(v) if v == "end" && profondeur > 0 => profondeur -= 1,
+                Token::Verb(v) if v == "end" => return self.advance(),
+                Token::Verb(v) if v == "else" && chercher && profondeur == 0 => {
+                    self.advance()?;
+                    self.conditions.push(debut);
+                    return Ok(());
+                }
+                Token::Verb(v) if v == "elif" && chercher && profondeur == 0 => {
+                    self.advance()?;
+                    if self.condition()? {
+                        self.conditions.push(debut);
+                        return Ok(());
+                    }
+                    continue; // La condition est consommée : on reprend le saut
                 }
+                _ => {}
             }
-            Token::Number(n) => Expression::Number(*n),
-            Token::StringLiteral(s) => Expression::StringLiteral(s.clone()),
-            Token::Register(r) => {
-                let _ = parse_register(r);
-                Expression::Register(r.clone())
+            // Une branche écartée peut viser une autre cible : ses fautes lexicales ne comptent pas
+            let _ = self.advance();
+        }
+    }
+
+    // Le corps d'une heka, jusqu'au 'end' qui la ferme
+    fn parse_corps_heka(
+        &mut self,
+        nom: &str,
+        debut: Span,
+        plancher: usize,
+        corps: &mut Vec<Spanned<Instruction>>,
+    ) -> ThotResult<()> {
+        loop {
+            if let Err(erreur) = self.parse_conditions(plancher) {
+                self.conditions.truncate(plancher);
+                self.skip_to_end();
+                return Err(erreur);
             }
-            _ => panic!(
-                "Syntax Error: Expression expected, found {:?}",
-                self.current_token
-            ),
-        };
-        self.advance();
-        while matches!(self.current_token, Token::Star | Token::Slash) {
-            let operateur = self.current_token.clone();
-            self.advance();
-            // Pour la multiplication, on ne regarde que les valeurs immédiates (pas d'addition ici)
-            let droite = match &self.current_token {
-                Token::Number(n) => Expression::Number(*n),
-                _ => panic!("Syntax Error: Opérateur '*' attend un nombre à droite"),
-            };
-            self.advance();
-            if let (Expression::Number(n1), Expression::Number(n2)) = (&gauche, &droite) {
-                gauche = match operateur {
-                    Token::Star => Expression::Number(n1 * n2),
-                    Token::Slash => Expression::Number(n1 / n2),
-                    _ => unreachable!(),
-                };
+            match self.current_token() {
+                Token::Verb(v) if v == "end" => {
+                    self.advance()?; // Consomme 'end'
+                    return Ok(());
+                }
+                Token::Eof => {
+                    return Err(ThotError::syntax(format!("heka '{nom}' is never closed"))
+                        .at(debut)
+                        .with_help("close the body with 'end'"));
+                }
+                Token::Verb(v)
+                    if matches!(v.as_str(), "heka" | "dema" | "ini" | "export" | "private") =>
+                {
+                    let erreur =
+                        self.unexpected(format!("'{v}' cannot appear inside heka '{nom}'"));
+                    self.skip_to_end();
+                    return Err(erreur);
+                }
+                _ => match self.parse_instruction() {
+                    Ok(instruction) => corps.push(instruction),
+                    Err(erreur) => {
+                        // On saute le reste du corps pour ne pas voir un 'end' orphelin
+                        self.skip_to_end();
+                        return Err(erreur);
+                    }
+                },
             }
         }
-        gauche
     }
-    // Analyse une instruction complète
-    pub fn parse_instruction(&mut self) -> Instruction {
-        match self.current_token() {
+
+    // Après une erreur dans le corps d'une heka : on avance jusqu'au 'end' qui la ferme
+    fn skip_to_end(&mut self) {
+        while self.not_eof() {
+            let fin = matches!(&self.current_token, Token::Verb(v) if v == "end");
+            // Une erreur du Lexer ici serait une conséquence de la première : on l'ignore
+            let _ = self.advance();
+            if fin {
+                return;
+            }
+        }
+    }
+
+    // Analyse une instruction complète et retient l'endroit où elle commence
+    pub fn parse_instruction(&mut self) -> ThotResult<Spanned<Instruction>> {
+        let span = self.current_span;
+        let instruction = self.parse_verb().map_err(|e| e.at(span))?;
+        Ok(Spanned::new(instruction, span))
+    }
+
+    fn parse_verb(&mut self) -> ThotResult<Instruction> {
+        let instruction = match self.current_token() {
             // Traduction de : sokh %registre
             Token::Verb(v) if v == "sokh" => {
-                self.advance(); // Consomme 'sokh'
+                self.advance()?; // Consomme 'sokh'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'sokh' requires a register as destination"),
+                    _ => return Err(self.unexpected("'sokh' requires a register as destination")),
                 };
-                let _ = parse_general_register(&destination);
-                self.advance(); // Consomme le registre
+                let _ = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
                 Instruction::Sokh { destination }
             }
+            // Traduction de : wah %registre
+            Token::Verb(v) if v == "wah" => {
+                self.advance()?; // Consomme 'wah'
+
+                let destination = match &self.current_token {
+                    Token::Register(r) => r.clone(),
+                    _ => return Err(self.unexpected("'wah' requires a register as destination")),
+                };
+                let _ = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
+                Instruction::Wah { destination }
+            }
             Token::Verb(v)
                 if v == "neheh" || v == "ankh" || v == "isfet" || v == "jena" || v == "dja" =>
             {
                 let verbe = v.clone();
-                self.advance();
-                let target = self.parse_expression(); // parse_expression gère déjà $ ou Identifiant !
+                self.advance()?;
+                let target = self.parse_expression()?; // parse_expression gère déjà $ ou Identifiant !
 
                 match verbe.as_str() {
                     "neheh" => Instruction::Neheh { target },
@@ -196,19 +502,28 @@ impl<'a> Parser<'a> {
                     "isfet" => Instruction::Isfet { target },
                     // Dans la fonction parse_instruction :
                     "dja" => {
-                        self.advance(); // On saute 'dja'
+                        self.advance()?; // On saute 'dja'
                         // On attend le segment (ex: 0x08)
                         let segment = match self.current_token() {
-                            Token::Number(n) => n as u16,
-                            _ => panic!("Error: 'dja' is waiting for a segment (number)"),
+                            Token::Number(n) => u16::try_from(n).map_err(|_| {
+                                ThotError::overflow(format!(
+                                    "Segment selector {n} does not fit in 16 bits"
+                                ))
+                                .at(self.current_span)
+                            })?,
+                            _ => {
+                                return Err(
+                                    self.unexpected("'dja' is waiting for a segment (number)")
+                                );
+                            }
                         };
-                        self.advance();
+                        self.advance()?;
 
                         // On attend les deux points ':'
-                        self.expect_token(Token::Colon);
+                        self.expect_token(Token::Colon)?;
 
                         // On récupère la target (label ou adresse)
-                        let target = self.parse_expression();
+                        let target = self.parse_expression()?;
 
                         Instruction::Dja { segment, target }
                     }
@@ -218,8 +533,8 @@ impl<'a> Parser<'a> {
 
             Token::Verb(v) if v == "her" || v == "kher" || v == "her_ankh" || v == "kher_ankh" => {
                 let type_saut = v.clone();
-                self.advance();
-                let target = self.parse_expression();
+                self.advance()?;
+                let target = self.parse_expression()?;
 
                 match type_saut.as_str() {
                     "her" => Instruction::Her { target },
@@ -229,79 +544,135 @@ impl<'a> Parser<'a> {
                 }
             }
 
+            // Les sauts sans signe, sur les drapeaux, et la boucle comptée sur %ib
+            Token::Verb(v)
+                if matches!(
+                    v.as_str(),
+                    "hery"
+                        | "khery"
+                        | "hery_ankh"
+                        | "khery_ankh"
+                        | "fai"
+                        | "nen_fai"
+                        | "bah"
+                        | "nen_bah"
+                        | "tep"
+                        | "nen_tep"
+                        | "mety"
+                        | "nen_mety"
+                        | "renpet"
+                ) =>
+            {
+                let type_saut = v.clone();
+                self.advance()?;
+                let target = self.parse_expression()?;
+
+                match type_saut.as_str() {
+                    "hery" => Instruction::Hery { target },
+                    "khery" => Instruction::Khery { target },
+                    "hery_ankh" => Instruction::HeryAnkh { target },
+                    "khery_ankh" => Instruction::KheryAnkh { target },
+                    "fai" => Instruction::Fai { target },
+                    "nen_fai" => Instruction::NenFai { target },
+                    "bah" => Instruction::Bah { target },
+                    "nen_bah" => Instruction::NenBah { target },
+                    "tep" => Instruction::Tep { target },
+                    "nen_tep" => Instruction::NenTep { target },
+                    "mety" => Instruction::Mety { target },
+                    "nen_mety" => Instruction::NenMety { target },
+                    _ => Instruction::Renpet { target },
+                }
+            }
+
             Token::Verb(v) if v == "henek" => {
-                self.advance(); // Consomme 'henek'
+                self.advance()?; // Consomme 'henek'
 
                 // On s'attend à un registre comme destination
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'henek' requires a register as destination"),
+                    _ => return Err(self.unexpected("'henek' requires a register as destination")),
                 };
-                self.advance(); // Consomme le registre
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
-                let value = self.parse_expression(); // Capture la valeur (ex: 10)
-                let dest_spec = parse_register(&destination);
+                let value = self.parse_expression()?; // Capture la valeur (ex: 10)
+                let dest_spec = parse_register(&destination)?;
                 match dest_spec.kind {
                     RegKind::Segment(_) => match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             if src_spec.level != Level::Base {
-                                panic!("Segment moves require base registers: %{src}");
+                                return Err(ThotError::size_mismatch(format!(
+                                    "Segment moves require base registers: %{src}"
+                                )));
                             }
                         }
-                        _ => panic!("Segment registers require a register source."),
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Segment registers require a register source.",
+                            ));
+                        }
                     },
                     RegKind::General(_) => {
                         if dest_spec.level <= Level::High {
                             match &value {
                                 Expression::Register(src) => {
-                                    let src_spec = parse_general_register(src);
+                                    let src_spec = parse_general_register(src)?;
                                     ensure_same_level(
                                         "henek",
                                         &destination,
                                         dest_spec.level,
                                         src,
                                         src_spec.level,
-                                    );
+                                    )?;
                                 }
                                 Expression::Helix { ra, apophis } => {
                                     ensure_helix_fits(
                                         "henek",
                                         &destination,
                                         dest_spec.level,
-                                        *ra as u128,
-                                        *apophis as u128,
-                                    );
+                                        *ra,
+                                        *apophis,
+                                    )?;
                                 }
                                 Expression::Number(n) => {
-                                    ensure_number_fits("henek", &destination, dest_spec.level, *n);
+                                    ensure_number_fits("henek", &destination, dest_spec.level, *n)?;
                                 }
                                 _ => {}
                             }
-                        } else if dest_spec.level == Level::Extreme {
+                        } else if dest_spec.level >= Level::Extreme {
                             match &value {
                                 Expression::Register(src) => {
-                                    let src_spec = parse_general_register(src);
+                                    let src_spec = parse_general_register(src)?;
                                     ensure_same_level(
                                         "henek",
                                         &destination,
                                         dest_spec.level,
                                         src,
                                         src_spec.level,
-                                    );
+                                    )?;
+                                }
+                                Expression::Helix { ra, apophis } => {
+                                    ensure_helix_fits(
+                                        "henek",
+                                        &destination,
+                                        dest_spec.level,
+                                        *ra,
+                                        *apophis,
+                                    )?;
+                                }
+                                _ => {
+                                    return Err(ThotError::syntax(
+                                        "Henek for 128-bit and 256-bit registers only accepts Helix literals or registers.",
+                                    ));
                                 }
-                                Expression::Helix { .. } => {}
-                                _ => panic!(
-                                    "Henek for 128-bit registers only accepts Helix literals or registers."
-                                ),
                             }
                         } else {
-                            panic!(
-                                "Henek does not yet support registers beyond Extreme: %{} ({})",
+                            return Err(ThotError::size_mismatch(format!(
+                                "Henek does not yet support Very registers: %{} ({})",
                                 destination, dest_spec.level
-                            );
+                            )));
                         }
                     }
                 }
@@ -310,67 +681,66 @@ impl<'a> Parser<'a> {
             }
             // Fais la même chose pour ankh, isfet, jena, her, etc.
             Token::Verb(v) if v == "smen" => {
-                self.advance();
+                self.advance()?;
                 let nom = match &self.current_token {
                     Token::Identifier(n) => n.clone(),
-                    _ => panic!("Smen required a name"),
-                };
-                self.advance();
-                self.expect_token(Token::Equals);
-                let valeur_expr = self.parse_expression();
-                if let Expression::Number(n) = valeur_expr {
-                    self.constant.insert(nom.clone(), n); // On mémorise la constante !
-                    Instruction::Smen { nom, valeur: n }
-                } else {
-                    panic!("Smen requires a fixed numerical value (Zep Tepi)");
+                    Token::Verb(_) => return Err(self.reserved_name("constant")),
+                    _ => return Err(self.unexpected("Smen required a name")),
+                };
+                self.advance()?;
+                self.expect_token(Token::Equals)?;
+                let valeur = self.parse_expression()?;
+                match valeur {
+                    Expression::Number(n) => {
+                        self.constant.insert(nom.clone(), n); // On mémorise la constante !
+                    }
+                    // Une constante définie plus loin : la table des symboles tranchera
+                    ref v if v.is_relocatable() => {}
+                    _ => {
+                        return Err(ThotError::syntax(
+                            "Smen requires a fixed numerical value (Zep Tepi)",
+                        ));
+                    }
                 }
+                Instruction::Smen { nom, valeur }
             }
             Token::Verb(v) if v == "kheper" => {
-                self.advance();
+                self.advance()?;
                 let source = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'kheper' requires a source registry"),
+                    _ => return Err(self.unexpected("'kheper' requires a source registry")),
                 };
-                let _ = parse_general_register(&source);
-                self.advance();
-                self.expect_token(Token::Comma);
+                let _ = parse_general_register(&source)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
 
                 // NOUVEAU : Gestion des crochets ou du nombre direct
                 let adresse = if self.current_token == Token::OpenBracket {
-                    self.advance(); // Mange '['
-                    let expr = self.parse_expression();
-                    self.expect_token(Token::CloseBracket); // Mange ']'
-                    expr
+                    self.parse_adresse()?
                 } else {
-                    self.parse_expression() // Nombre direct (ancien mode)
+                    self.parse_expression()? // Nombre direct (ancien mode)
                 };
 
                 Instruction::Kheper { source, adresse }
             }
             // Traduction de : sena %registre, adresse
             Token::Verb(v) if v == "sena" => {
-                self.advance(); // Consomme 'sena'
+                self.advance()?; // Consomme 'sena'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'sena' exige un registre destination"),
+                    _ => return Err(self.unexpected("'sena' exige un registre destination")),
                 };
-                let _ = parse_general_register(&destination);
-                self.advance(); // Consomme le registre
+                let _ = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
                 // Gestion des crochets pour les pointeurs dynamiques [%ba]
                 let adresse = if self.current_token == Token::OpenBracket {
-                    self.advance(); // Mange '['
-                    let expr = self.parse_expression();
-                    self.expect_token(Token::CloseBracket); // Mange ']'
-                    if let Expression::Register(r) = &expr {
-                        let _ = parse_general_register(r);
-                    }
-                    expr
+                    self.parse_adresse()? // [%ba], [%hba + %hsi*4 + 16], [label + %hdi]...
                 } else {
-                    self.parse_expression() // Nombre direct (ex: 500)
+                    self.parse_expression()? // Nombre direct (ex: 500)
                 };
 
                 Instruction::Sena {
@@ -380,586 +750,1115 @@ impl<'a> Parser<'a> {
             }
             // Dans src/parser.rs (dans la méthode parse_instruction)
             Token::Verb(v) if v == "dema" => {
-                self.advance(); // Consomme 'dema'
-                let path = match self.parse_expression() {
+                let ligne = self.current_span.line;
+                self.advance()?; // Consomme 'dema'
+                // dema <pilotes/ecran> : une tablette cherchée uniquement dans les dossiers système
+                let (path, system) = if let Token::SystemPath(path) = &self.current_token {
+                    let path = path.clone();
+                    self.advance()?;
+                    (path, true)
+                } else {
+                    match self.parse_expression()? {
+                        Expression::StringLiteral(s) => (s, false),
+                        _ => {
+                            return Err(self
+                                .unexpected("'dema' waits for the path of the scroll in quotes"));
+                        }
+                    }
+                };
+                // dema "clavier" as kbd : les symboles de la tablette deviennent kbd.symbole
+                let alias = match &self.current_token {
+                    Token::Identifier(mot) if mot == "as" && self.current_span.line == ligne => {
+                        self.advance()?; // Consomme 'as'
+                        let alias = match &self.current_token {
+                            Token::Identifier(nom) if !nom.contains('.') => nom.clone(),
+                            Token::Verb(_) => return Err(self.reserved_name("namespace")),
+                            _ => return Err(self.unexpected("'as' waits for a namespace name")),
+                        };
+                        self.advance()?;
+                        Some(alias)
+                    }
+                    _ => None,
+                };
+                Instruction::Dema {
+                    path,
+                    system,
+                    alias,
+                }
+            }
+            // Traduction de : sesh 1, 2, "texte" (et msesh, hsesh, vsesh pour 16, 32, 64 bits)
+            Token::Verb(v) if v == "sesh" || v == "msesh" || v == "hsesh" || v == "vsesh" => {
+                let level = match v.as_str() {
+                    "msesh" => Level::Medium,
+                    "hsesh" => Level::High,
+                    "vsesh" => Level::Very,
+                    _ => Level::Base,
+                };
+                self.advance()?;
+                let mut values = vec![self.parse_expression()?];
+                while self.current_token == Token::Comma {
+                    self.advance()?; // Consomme la virgule
+                    values.push(self.parse_expression()?);
+                }
+                Instruction::Sesh { level, values }
+            }
+            // Traduction de : tut, meh, sia (et m..., h... pour 16 et 32 bits par élément)
+            Token::Verb(v)
+                if matches!(
+                    v.as_str(),
+                    "tut" | "mtut" | "htut" | "meh" | "mmeh" | "hmeh" | "sia" | "msia" | "hsia"
+                ) =>
+            {
+                let level = match v.as_str() {
+                    "mtut" | "mmeh" | "msia" => Level::Medium,
+                    "htut" | "hmeh" | "hsia" => Level::High,
+                    _ => Level::Base,
+                };
+                let instruction = if v.ends_with("tut") {
+                    Instruction::Tut { level }
+                } else if v.ends_with("meh") {
+                    Instruction::Meh { level }
+                } else {
+                    Instruction::Sia { level }
+                };
+                self.advance()?;
+                instruction
+            }
+            // Traduction de : djed 16 (alignement)
+            Token::Verb(v) if v == "djed" => {
+                self.advance()?;
+                Instruction::Djed {
+                    alignment: self.parse_expression()?,
+                }
+            }
+            // Traduction de : shu 64 (réservation)
+            Token::Verb(v) if v == "shu" => {
+                self.advance()?;
+                Instruction::Shu {
+                    size: self.parse_expression()?,
+                }
+            }
+            // Traduction de : heh 8 instruction (répétition)
+            Token::Verb(v) if v == "heh" => {
+                self.advance()?;
+                let count = self.parse_expression()?;
+                let instruction = self.parse_instruction()?;
+                match &instruction.node {
+                    Instruction::Label(_)
+                    | Instruction::Invocation { .. }
+                    | Instruction::Heka { .. }
+                    | Instruction::Dema { .. }
+                    | Instruction::Ini { .. }
+                    | Instruction::Export(_)
+                    | Instruction::Private(_)
+                    | Instruction::Smen { .. }
+                    | Instruction::Nehes { .. }
+                    | Instruction::Nama { .. } => {
+                        return Err(ThotError::syntax(
+                            "'heh' only repeats a single instruction or data directive",
+                        )
+                        .at(instruction.span));
+                    }
+                    _ => {}
+                }
+                Instruction::Heh {
+                    count,
+                    instruction: Box::new(instruction),
+                }
+            }
+            // Traduction de : ini "police.bin" (inclusion binaire)
+            Token::Verb(v) if v == "ini" => {
+                self.advance()?;
+                let path = match self.parse_expression()? {
                     Expression::StringLiteral(s) => s,
-                    _ => panic!("Syntax Error: 'dema' waits for the path of the scroll in quotes"),
+                    _ => {
+                        return Err(
+                            self.unexpected("'ini' waits for the path of the file in quotes")
+                        );
+                    }
                 };
-                Instruction::Dema { path }
+                Instruction::Ini { path }
+            }
+            // Traduction de : export nom, ... / private nom, ...
+            Token::Verb(v) if v == "export" || v == "private" => {
+                self.advance()?;
+                let mut noms = Vec::new();
+                loop {
+                    match &self.current_token {
+                        Token::Identifier(nom) => noms.push(nom.clone()),
+                        Token::Verb(_) => return Err(self.reserved_name("symbol")),
+                        _ => return Err(self.unexpected(format!("'{v}' waits for symbol names"))),
+                    }
+                    self.advance()?;
+                    if self.current_token != Token::Comma {
+                        break;
+                    }
+                    self.advance()?; // Consomme la virgule
+                }
+                if v == "export" {
+                    Instruction::Export(noms)
+                } else {
+                    Instruction::Private(noms)
+                }
             }
             Token::Verb(v) if v == "rdtsc" => {
-                self.advance();
+                self.advance()?;
                 Instruction::Rdtsc
             }
             // Traduction de : henet %registre, valeur (AND)
             Token::Verb(v) if v == "henet" => {
-                self.advance();
+                self.advance()?;
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'henet' exige un registre"),
+                    _ => return Err(self.unexpected("'henet' exige un registre")),
                 };
-                let dest_spec = parse_general_register(&destination);
-                self.advance();
-                self.expect_token(Token::Comma);
-                let value = self.parse_expression();
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
+                let value = self.parse_expression()?;
                 if dest_spec.level <= Level::High {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "henet",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
                         }
                         Expression::Helix { ra, apophis } => {
                             ensure_helix_fits(
                                 "henet",
                                 &destination,
                                 dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                                *ra,
+                                *apophis,
+                            )?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("henet", &destination, dest_spec.level, *n);
+                            ensure_number_fits("henet", &destination, dest_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "henet",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits(
+                                "henet",
+                                &destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Henet for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Henet for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Henet does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
                 Instruction::Henet { destination, value }
             }
 
             // Traduction de : mer %registre, valeur (OR)
             Token::Verb(v) if v == "mer" => {
-                self.advance();
+                self.advance()?;
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'mer' requires a registry"),
+                    _ => return Err(self.unexpected("'mer' requires a registry")),
                 };
-                let dest_spec = parse_general_register(&destination);
-                self.advance();
-                self.expect_token(Token::Comma);
-                let value = self.parse_expression();
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
+                let value = self.parse_expression()?;
                 if dest_spec.level <= Level::High {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "mer",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
                         }
                         Expression::Helix { ra, apophis } => {
-                            ensure_helix_fits(
-                                "mer",
-                                &destination,
-                                dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                            ensure_helix_fits("mer", &destination, dest_spec.level, *ra, *apophis)?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("mer", &destination, dest_spec.level, *n);
+                            ensure_number_fits("mer", &destination, dest_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "mer",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits("mer", &destination, dest_spec.level, *ra, *apophis)?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Mer for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Mer for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Mer does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
                 Instruction::Mer { destination, value }
             }
+            // Traduction de : pesh %registre, valeur (XOR) et maa %registre, valeur (TEST)
+            Token::Verb(v) if v == "pesh" || v == "maa" => {
+                let verbe = v.clone();
+                self.advance()?;
+                let destination = match &self.current_token {
+                    Token::Register(r) => r.clone(),
+                    _ => return Err(self.unexpected(format!("'{verbe}' requires a registry"))),
+                };
+                let dest_spec = parse_general_register(&destination)?;
+                ensure_helix_level(&verbe, &destination, dest_spec.level)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
+                let value = self.parse_expression()?;
+                match &value {
+                    Expression::Register(src) => {
+                        let src_spec = parse_general_register(src)?;
+                        ensure_same_level(
+                            &verbe,
+                            &destination,
+                            dest_spec.level,
+                            src,
+                            src_spec.level,
+                        )?;
+                    }
+                    Expression::Helix { ra, apophis } => {
+                        ensure_helix_fits(&verbe, &destination, dest_spec.level, *ra, *apophis)?;
+                    }
+                    Expression::Number(n) if dest_spec.level <= Level::High => {
+                        ensure_number_fits(&verbe, &destination, dest_spec.level, *n)?;
+                    }
+                    _ if dest_spec.level > Level::High => {
+                        return Err(ThotError::syntax(format!(
+                            "'{verbe}' for Extreme and Zenith registers only accepts Helix literals or registers."
+                        )));
+                    }
+                    _ => {}
+                }
+                if verbe == "pesh" {
+                    Instruction::Pesh { destination, value }
+                } else {
+                    Instruction::Maa { destination, value }
+                }
+            }
+
+            // Traduction de : kem %registre (NOT) et hem %registre (NEG)
+            Token::Verb(v) if v == "kem" || v == "hem" => {
+                let verbe = v.clone();
+                self.advance()?;
+                let destination = match &self.current_token {
+                    Token::Register(r) => r.clone(),
+                    _ => return Err(self.unexpected(format!("'{verbe}' requires a registry"))),
+                };
+                let dest_spec = parse_general_register(&destination)?;
+                ensure_helix_level(&verbe, &destination, dest_spec.level)?;
+                self.advance()?;
+                if verbe == "kem" {
+                    Instruction::Kem { destination }
+                } else {
+                    Instruction::Hem { destination }
+                }
+            }
+
+            // Traduction de : wer %registre, compte (SHL), nedjes (SHR), nedjes_ankh (SAR),
+            // deben (ROL), nebed (ROR). Le compte est un nombre ou %da.
+            Token::Verb(v)
+                if matches!(
+                    v.as_str(),
+                    "wer" | "nedjes" | "nedjes_ankh" | "deben" | "nebed"
+                ) =>
+            {
+                let verbe = v.clone();
+                self.advance()?;
+                let destination = match &self.current_token {
+                    Token::Register(r) => r.clone(),
+                    _ => return Err(self.unexpected(format!("'{verbe}' requires a registry"))),
+                };
+                let dest_spec = parse_general_register(&destination)?;
+                ensure_helix_level(&verbe, &destination, dest_spec.level)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
+                let count = self.parse_expression()?;
+                ensure_shift_count(&verbe, &destination, dest_spec.level, &count)?;
+                match verbe.as_str() {
+                    "wer" => Instruction::Wer { destination, count },
+                    "nedjes" => Instruction::Nedjes { destination, count },
+                    "nedjes_ankh" => Instruction::NedjesAnkh { destination, count },
+                    "deben" => Instruction::Deben { destination, count },
+                    _ => Instruction::Nebed { destination, count },
+                }
+            }
             Token::Verb(v) if v == "duat" => {
-                self.advance(); // Consomme 'duat'
-                let phrase = match self.parse_expression() {
+                self.advance()?; // Consomme 'duat'
+                let phrase = match self.parse_expression()? {
                     Expression::StringLiteral(s) => s,
-                    _ => panic!("Syntax Error: 'duat' attend une phrase entre guillemets"),
+                    _ => return Err(self.unexpected("'duat' attend une phrase entre guillemets")),
                 };
-                self.expect_token(Token::Comma);
-                let address = match self.parse_expression() {
-                    Expression::Number(n) => n as u16,
-                    _ => panic!("Syntax Error: 'duat' attend une adresse numérique"),
+                self.expect_token(Token::Comma)?;
+                let address = match self.parse_expression()? {
+                    Expression::Number(n) => u16::try_from(n).map_err(|_| {
+                        ThotError::overflow(format!("'duat' address {n} does not fit in 16 bits"))
+                    })?,
+                    _ => return Err(self.unexpected("'duat' attend une adresse numérique")),
                 };
                 Instruction::Duat { phrase, address }
             }
             // Dans src/parser.rs, dans parse_instruction
             Token::Verb(v) if v == "push" => {
-                self.advance();
-                let target = self.parse_expression();
+                self.advance()?;
+                let target = self.parse_expression()?;
                 if let Expression::Register(r) = &target {
-                    let _ = parse_general_register(r);
+                    let _ = parse_general_register(r)?;
                 }
                 Instruction::Push { target }
             }
             Token::Verb(v) if v == "pop" => {
-                self.advance();
+                self.advance()?;
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'pop' exige un registre"),
+                    _ => return Err(self.unexpected("'pop' exige un registre")),
                 };
-                let _ = parse_general_register(&destination);
-                self.advance();
+                let _ = parse_general_register(&destination)?;
+                self.advance()?;
                 Instruction::Pop { destination }
             }
             Token::Verb(v) if v == "in" => {
-                self.advance();
-                let port = self.parse_expression(); // Ex: 0x60 pour le clavier
+                self.advance()?;
+                let port = self.parse_expression()?; // Ex: 0x60 pour le clavier
                 if let Expression::Register(r) = &port {
-                    let reg_spec = parse_general_register(r);
+                    let reg_spec = parse_general_register(r)?;
                     if !matches!(reg_spec.kind, RegKind::General(RegBase::Da))
                         || reg_spec.level != Level::Base
                     {
-                        panic!("Syntax Error: 'in' requires %da as register port");
+                        return Err(ThotError::syntax("'in' requires %da as register port"));
                     }
                 }
                 Instruction::In { port }
             }
             Token::Verb(v) if v == "out" => {
-                self.advance();
-                let port = self.parse_expression(); // Ex: 0x3D4 pour la carte VGA
+                self.advance()?;
+                let port = self.parse_expression()?; // Ex: 0x3D4 pour la carte VGA
                 if let Expression::Register(r) = &port {
-                    let reg_spec = parse_general_register(r);
+                    let reg_spec = parse_general_register(r)?;
                     if !matches!(reg_spec.kind, RegKind::General(RegBase::Da))
                         || reg_spec.level != Level::Base
                     {
-                        panic!("Syntax Error: 'out' requires %da as register port");
+                        return Err(ThotError::syntax("'out' requires %da as register port"));
                     }
                 }
                 Instruction::Out { port }
             }
 
             Token::Verb(v) if v == "wab" => {
-                self.advance(); // Consomme le mot 'wab'
+                self.advance()?; // Consomme le mot 'wab'
                 Instruction::Wab
             }
+            // Traduction de : nehes 0x21, clavier (gestionnaire d'interruption)
+            Token::Verb(v) if v == "nehes" => {
+                self.advance()?;
+                let vector = self.parse_expression()?;
+                self.expect_token(Token::Comma)?;
+                let handler = self.parse_expression()?;
+                if !matches!(handler, Expression::Identifier(_)) {
+                    return Err(ThotError::syntax(
+                        "'nehes' binds an interrupt vector to a label",
+                    ));
+                }
+                Instruction::Nehes { vector, handler }
+            }
+            // Traduction de : nis 0x80 (INT)
+            Token::Verb(v) if v == "nis" => {
+                self.advance()?;
+                Instruction::Nis {
+                    vector: self.parse_expression()?,
+                }
+            }
+            Token::Verb(v) if matches!(v.as_str(), "iwi" | "khetem" | "wen" | "hotep") => {
+                let instruction = match v.as_str() {
+                    "iwi" => Instruction::Iwi,
+                    "khetem" => Instruction::Khetem,
+                    "wen" => Instruction::Wen,
+                    _ => Instruction::Hotep,
+                };
+                self.advance()?;
+                instruction
+            }
             // Traduction du saut conditionnel : ankh target
             Token::Verb(v) if v == "ankh" => {
-                self.advance(); // Consomme 'ankh'
+                self.advance()?; // Consomme 'ankh'
                 let _target = match &self.current_token {
                     Token::Identifier(i) => i.clone(),
-                    _ => panic!("Syntax Error: 'ankh' requires a target label"),
+                    _ => return Err(ThotError::syntax("'ankh' requires a target label")),
                 };
-                self.advance(); // Consomme la target
+                self.advance()?; // Consomme la target
                 Instruction::Ankh {
-                    target: self.parse_expression(),
+                    target: self.parse_expression()?,
                 }
             }
             // Dans src/parser.rs, fonction parse_instruction
             Token::Verb(v) if v == "kherp" => {
-                self.advance(); // On consomme "kherp"
+                self.advance()?; // On consomme "kherp"
                 Instruction::Kherp
             }
             // Traduction du saut inconditionnel : neheh target
             Token::Verb(v) if v == "neheh" => {
-                self.advance(); // Consomme 'neheh'
+                self.advance()?; // Consomme 'neheh'
                 if let Token::Identifier(_target) = self.current_token.clone() {
-                    self.advance(); // Consomme la target (SANS CHERCHER DE ':')
+                    self.advance()?; // Consomme la target (SANS CHERCHER DE ':')
                     Instruction::Neheh {
-                        target: self.parse_expression(),
+                        target: self.parse_expression()?,
                     }
                 } else {
-                    panic!("Syntax Error: 'neheh' attend une target.");
+                    return Err(self.unexpected("'neheh' attend une target."));
                 }
             }
             Token::Verb(v) if v == "sedjem" => {
-                self.advance(); // Consomme 'sedjem'
+                self.advance()?; // Consomme 'sedjem'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'sedjem' requires a register as destination"),
+                    _ => return Err(self.unexpected("'sedjem' requires a register as destination")),
                 };
-                let dest_spec = parse_general_register(&destination);
+                let dest_spec = parse_general_register(&destination)?;
                 if !matches!(dest_spec.kind, RegKind::General(RegBase::Ka))
                     || dest_spec.level != Level::Base
                 {
-                    panic!("Syntax Error: 'sedjem' requires %ka as destination");
+                    return Err(ThotError::syntax("'sedjem' requires %ka as destination"));
                 }
-                self.advance(); // Consomme le registre
+                self.advance()?; // Consomme le registre
 
                 Instruction::Sedjem { destination }
             }
             Token::Verb(v) if v == "per" => {
-                self.advance(); // Consomme 'per'
-                let message = self.parse_expression(); // Capture le message
+                self.advance()?; // Consomme 'per'
+                let message = self.parse_expression()?; // Capture le message
                 Instruction::Per { message }
             }
             // Traduction de : sema %registre, valeur
             Token::Verb(v) if v == "sema" => {
-                self.advance(); // Consomme 'sema'
+                self.advance()?; // Consomme 'sema'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'sema' requires a register as destination"),
+                    _ => return Err(self.unexpected("'sema' requires a register as destination")),
                 };
-                let dest_spec = parse_general_register(&destination);
-                self.advance(); // Consomme le registre
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
-                let value = self.parse_expression(); // Capture la force à unir
+                let value = self.parse_expression()?; // Capture la force à unir
                 if dest_spec.level <= Level::High {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "sema",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
                         }
                         Expression::Helix { ra, apophis } => {
                             ensure_helix_fits(
                                 "sema",
                                 &destination,
                                 dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                                *ra,
+                                *apophis,
+                            )?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("sema", &destination, dest_spec.level, *n);
+                            ensure_number_fits("sema", &destination, dest_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "sema",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits(
+                                "sema",
+                                &destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Sema for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Sema for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else if dest_spec.level == Level::Zenith {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "sema",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits(
+                                "sema",
+                                &destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Sema for 256-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Sema for 256-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Sema does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
                 Instruction::Sema { destination, value }
             }
             // Traduction de : shesa %registre, valeur
             Token::Verb(v) if v == "shesa" => {
-                self.advance(); // Consomme 'shesa'
+                self.advance()?; // Consomme 'shesa'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'shesa' requires a register as destination"),
+                    _ => return Err(self.unexpected("'shesa' requires a register as destination")),
                 };
-                let dest_spec = parse_general_register(&destination);
-                self.advance(); // Consomme le registre
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
-                let value = self.parse_expression(); // Capture la force à multiplier
+                let value = self.parse_expression()?; // Capture la force à multiplier
                 if dest_spec.level <= Level::High {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "shesa",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
                         }
                         Expression::Helix { ra, apophis } => {
                             ensure_helix_fits(
                                 "shesa",
                                 &destination,
                                 dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                                *ra,
+                                *apophis,
+                            )?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("shesa", &destination, dest_spec.level, *n);
+                            ensure_number_fits("shesa", &destination, dest_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "shesa",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits(
+                                "shesa",
+                                &destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Shesa for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Shesa for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Shesa does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
                 Instruction::Shesa { destination, value }
             }
 
+            // Traduction de : wep %ka, diviseur (DIV) et wep_ankh %ka, diviseur (IDIV)
+            Token::Verb(v) if v == "wep" || v == "wep_ankh" => {
+                let verbe = v.clone();
+                self.advance()?;
+                let destination = match &self.current_token {
+                    Token::Register(r) => r.clone(),
+                    _ => return Err(self.unexpected(format!("'{verbe}' requires a registry"))),
+                };
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?;
+                self.expect_token(Token::Comma)?;
+                let value = self.parse_expression()?;
+                ensure_division(&verbe, &destination, dest_spec.level, &value)?;
+                if verbe == "wep" {
+                    Instruction::Wep { destination, value }
+                } else {
+                    Instruction::WepAnkh { destination, value }
+                }
+            }
+
             // Traduction de : wdj %registre, valeur
             Token::Verb(v) if v == "wdj" => {
-                self.advance(); // Consomme 'wdj'
+                self.advance()?; // Consomme 'wdj'
 
                 let left = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'wdj' requires a register on the left"),
+                    _ => return Err(self.unexpected("'wdj' requires a register on the left")),
                 };
-                let left_spec = parse_general_register(&left);
-                self.advance(); // Consomme le registre
+                let left_spec = parse_general_register(&left)?;
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
-                let right = self.parse_expression(); // Capture la valeur à peser
+                let right = self.parse_expression()?; // Capture la valeur à peser
                 if left_spec.level <= Level::High {
                     match &right {
                         Expression::Register(r) => {
-                            let right_spec = parse_general_register(r);
-                            ensure_same_level("wdj", &left, left_spec.level, r, right_spec.level);
+                            let right_spec = parse_general_register(r)?;
+                            ensure_same_level("wdj", &left, left_spec.level, r, right_spec.level)?;
                         }
                         Expression::Helix { ra, apophis } => {
-                            ensure_helix_fits(
-                                "wdj",
-                                &left,
-                                left_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                            ensure_helix_fits("wdj", &left, left_spec.level, *ra, *apophis)?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("wdj", &left, left_spec.level, *n);
+                            ensure_number_fits("wdj", &left, left_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if left_spec.level == Level::Extreme {
                     match &right {
                         Expression::Register(r) => {
-                            let right_spec = parse_general_register(r);
-                            ensure_same_level("wdj", &left, left_spec.level, r, right_spec.level);
+                            let right_spec = parse_general_register(r)?;
+                            ensure_same_level("wdj", &left, left_spec.level, r, right_spec.level)?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits("wdj", &left, left_spec.level, *ra, *apophis)?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Wdj for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Wdj for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Wdj does not yet support registers beyond Extreme: %{} ({})",
                         left, left_spec.level
-                    );
+                    )));
                 }
                 Instruction::Wdj { left, right }
             }
 
             // Traduction de : returne valeur
             Token::Verb(v) if v == "return" => {
-                self.advance(); // Consomme 'returne'
-                let resultat = self.parse_expression(); // Capture ce qu'on renvoie
+                self.advance()?; // Consomme 'returne'
+                let resultat = self.parse_expression()?; // Capture ce qu'on renvoie
                 if let Expression::Register(r) = &resultat {
-                    let reg_spec = parse_general_register(r);
+                    let reg_spec = parse_general_register(r)?;
                     if !matches!(reg_spec.kind, RegKind::General(RegBase::Ka))
                         || reg_spec.level != Level::Base
                     {
-                        panic!("Syntax Error: 'return' only supports %ka as a register result");
+                        return Err(ThotError::syntax(
+                            "'return' only supports %ka as a register result",
+                        ));
                     }
                 }
                 Instruction::Return { resultat } // (Assure-toi que ça correspond au nom exact dans ton ast.rs)
             }
             Token::Identifier(name) => {
-                self.advance(); // Consomme le nom
+                let ligne = self.current_span.line;
+                self.advance()?; // Consomme le nom
                 if self.current_token == Token::Colon {
-                    self.advance(); // Consomme le ':'
-                    Instruction::Label(name)
+                    self.advance()?; // Consomme le ':'
+                    // Un label global ouvre une nouvelle portée pour les labels locaux
+                    if !name.contains('.') {
+                        self.scope = Some(name.clone());
+                    }
+                    Instruction::Label(self.qualify(name))
                 } else {
-                    // LE SORTILÈGE DE RÉVÉLATION :
-                    panic!(
-                        "Syntax Error: Au debut d'une ligne, '{}' doit etre suivi de ':'. Mais Thot a trouve ceci a la place : {:?}",
-                        name, self.current_token
-                    );
+                    // Sinon c'est l'appel d'une heka : ses arguments suivent sur la même ligne.
+                    // Un nom inconnu sera signalé au dépliage, une fois toutes les heka connues.
+                    let mut arguments = Vec::new();
+                    if self.current_span.line == ligne && self.not_eof() {
+                        loop {
+                            arguments.push(self.parse_expression()?);
+                            if self.current_token != Token::Comma {
+                                break;
+                            }
+                            self.advance()?; // Consomme la virgule
+                        }
+                    }
+                    Instruction::Invocation {
+                        nom: name,
+                        arguments,
+                    }
                 }
             }
+            // Traduction de : heka nom param, ... (corps) end
+            Token::Verb(v) if v == "heka" => {
+                let debut = self.current_span;
+                self.advance()?; // Consomme 'heka'
+                let nom = match &self.current_token {
+                    Token::Identifier(n) => n.clone(),
+                    Token::Verb(_) => return Err(self.reserved_name("heka")),
+                    _ => return Err(self.unexpected("'heka' requires a macro name")),
+                };
+                let ligne = self.current_span.line;
+                self.advance()?; // Consomme le nom
+
+                let mut parametres: Vec<String> = Vec::new();
+                while self.current_span.line == ligne && self.not_eof() {
+                    match &self.current_token {
+                        Token::Identifier(p) if parametres.contains(p) => {
+                            return Err(self.unexpected(format!(
+                                "Parameter '{p}' appears twice in heka '{nom}'"
+                            )));
+                        }
+                        Token::Identifier(p) => parametres.push(p.clone()),
+                        Token::Verb(_) => return Err(self.reserved_name("parameter")),
+                        _ => {
+                            return Err(self.unexpected(format!(
+                                "heka parameters must be names, found {:?}",
+                                self.current_token
+                            )));
+                        }
+                    }
+                    self.advance()?;
+                    if self.current_token != Token::Comma {
+                        break;
+                    }
+                    self.advance()?; // Consomme la virgule
+                }
+
+                let mut corps = Vec::new();
+                let plancher = self.conditions.len();
+                // Les labels locaux du corps ne dépendent pas de l'endroit où la heka est définie
+                let portee = self.scope.take();
+                let corps_lu = self.parse_corps_heka(&nom, debut, plancher, &mut corps);
+                self.scope = portee;
+                corps_lu?;
+                Instruction::Heka {
+                    nom,
+                    parametres,
+                    corps,
+                }
+            }
+            Token::Verb(v) if v == "end" => {
+                return Err(self.unexpected("'end' without an opening 'heka' or 'if'"));
+            }
             // Traduction de : nama variable = valeur
             Token::Verb(v) if v == "nama" => {
-                self.advance(); // Consomme 'nama'
+                self.advance()?; // Consomme 'nama'
 
                 // 1. On vérifie qu'on a bien un nom de variable (Identifiant)
                 let name = match &self.current_token {
                     Token::Identifier(i) => i.clone(),
-                    _ => panic!(
-                        "Syntax Error: Le verbe 'nama' exige un nom de variable (ex: nama age = 10)"
-                    ),
+                    Token::Verb(_) => return Err(self.reserved_name("variable")),
+                    _ => {
+                        return Err(self.unexpected(
+                            "Le verbe 'nama' exige un nom de variable (ex: nama age = 10)",
+                        ));
+                    }
                 };
-                self.advance(); // Consomme le nom de la variable
+                self.advance()?; // Consomme le nom de la variable
 
                 // 2. On s'assure qu'il y a bien le symbole '='
-                self.expect_token(Token::Equals);
+                self.expect_token(Token::Equals)?;
 
                 // 3. On capture ce qu'il y a après le '=' (un nombre, une phrase, etc.)
-                let value = self.parse_expression();
+                let value = self.parse_expression()?;
 
                 Instruction::Nama { name, value }
             }
             // Traduction du saut conditionnel : isfet target (Saut si Différent)
             Token::Verb(v) if v == "isfet" => {
-                self.advance(); // Consomme 'isfet'
+                self.advance()?; // Consomme 'isfet'
                 let _target = match &self.current_token {
                     Token::Identifier(i) => i.clone(),
-                    _ => panic!("Syntax Error: 'isfet' exige une etiquette target"),
+                    _ => return Err(self.unexpected("'isfet' exige une etiquette target")),
                 };
-                self.advance();
+                self.advance()?;
 
                 Instruction::Isfet {
-                    target: self.parse_expression(),
+                    target: self.parse_expression()?,
                 }
             }
             // Traduction de : kheb %registre, valeur&
             Token::Verb(v) if v == "kheb" => {
-                self.advance(); // Consomme 'kheb'
+                self.advance()?; // Consomme 'kheb'
 
                 let destination = match &self.current_token {
                     Token::Register(r) => r.clone(),
-                    _ => panic!("Syntax Error: 'kheb' requires a register as destination"),
+                    _ => return Err(self.unexpected("'kheb' requires a register as destination")),
                 };
-                let dest_spec = parse_general_register(&destination);
-                self.advance(); // Consomme le registre
+                let dest_spec = parse_general_register(&destination)?;
+                self.advance()?; // Consomme le registre
 
-                self.expect_token(Token::Comma); // Consomme la virgule
+                self.expect_token(Token::Comma)?; // Consomme la virgule
 
-                let value = self.parse_expression(); // Capture la valeur à soustraire
+                let value = self.parse_expression()?; // Capture la valeur à soustraire
                 if dest_spec.level <= Level::High {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "kheb",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
                         }
                         Expression::Helix { ra, apophis } => {
                             ensure_helix_fits(
                                 "kheb",
                                 &destination,
                                 dest_spec.level,
-                                *ra as u128,
-                                *apophis as u128,
-                            );
+                                *ra,
+                                *apophis,
+                            )?;
                         }
                         Expression::Number(n) => {
-                            ensure_number_fits("kheb", &destination, dest_spec.level, *n);
+                            ensure_number_fits("kheb", &destination, dest_spec.level, *n)?;
                         }
                         _ => {}
                     }
                 } else if dest_spec.level == Level::Extreme {
                     match &value {
                         Expression::Register(src) => {
-                            let src_spec = parse_general_register(src);
+                            let src_spec = parse_general_register(src)?;
                             ensure_same_level(
                                 "kheb",
                                 &destination,
                                 dest_spec.level,
                                 src,
                                 src_spec.level,
-                            );
+                            )?;
+                        }
+                        Expression::Helix { ra, apophis } => {
+                            ensure_helix_fits(
+                                "kheb",
+                                &destination,
+                                dest_spec.level,
+                                *ra,
+                                *apophis,
+                            )?;
+                        }
+                        _ => {
+                            return Err(ThotError::syntax(
+                                "Kheb for 128-bit registers only accepts Helix literals or registers.",
+                            ));
                         }
-                        Expression::Helix { .. } => {}
-                        _ => panic!(
-                            "Kheb for 128-bit registers only accepts Helix literals or registers."
-                        ),
                     }
                 } else {
-                    panic!(
+                    return Err(ThotError::size_mismatch(format!(
                         "Kheb does not yet support registers beyond Extreme: %{} ({})",
                         destination, dest_spec.level
-                    );
+                    )));
                 }
                 Instruction::Kheb { destination, value }
             }
             // (On ajoutera 'wdj', 'sema', etc. ici plus tard)
-            _ => panic!("Syntax Error: Unknown instruction {:?}", self.current_token),
+            _ => {
+                return Err(
+                    self.unexpected(format!("Unknown instruction {:?}", self.current_token))
+                );
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+// Combine deux opérandes : les nombres sont repliés tout de suite ; labels, variables
+// et '$' sont laissés à l'Émetteur, qui finira le calcul après la mise en page
+fn combiner(op: BinaryOp, gauche: Expression, droite: Expression) -> ThotResult<Expression> {
+    match (gauche, droite) {
+        (Expression::Number(n1), Expression::Number(n2)) => {
+            Ok(Expression::Number(op.apply(n1, n2)?))
         }
+        (g, d) if operande_liable(&g) && operande_liable(&d) => Ok(Expression::Binary {
+            op,
+            left: Box::new(g),
+            right: Box::new(d),
+        }),
+        _ => Err(ThotError::syntax(format!(
+            "'{op}' only combines numbers, labels, variables and '$'"
+        ))),
+    }
+}
+
+// Un opérande qu'on peut combiner : un nombre, ou une valeur connue à l'édition des liens
+fn operande_liable(expression: &Expression) -> bool {
+    matches!(expression, Expression::Number(_)) || expression.is_relocatable()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::source::Span;
+
+    fn expression(source: &str) -> ThotResult<Expression> {
+        Parser::new(Lexer::new(source, 0))?.parse_expression()
+    }
+
+    fn valeur(source: &str) -> i64 {
+        match expression(source) {
+            Ok(Expression::Number(n)) => n,
+            autre => panic!("{source}: {autre:?}"),
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(valeur("1 + 2 * 3"), 7);
+        assert_eq!(valeur("2 * 3 + 4"), 10);
+        assert_eq!(valeur("7 % 4 * 2"), 6);
+        assert_eq!(valeur("1 | 6 & 3"), 3);
+        assert_eq!(valeur("1 ^ 3 & 1"), 0);
+        // Comme en Rust (et non en C), & lie plus fort que ==
+        assert_eq!(valeur("6 & 3 == 2"), 1);
+        assert_eq!(valeur("1 + 1 == 2"), 1);
+        assert_eq!(valeur("2 < 3 + 1"), 1);
+        // Associativité à gauche
+        assert_eq!(valeur("10 - 4 - 3"), 3);
+        assert_eq!(valeur("64 / 4 / 2"), 8);
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_eq!(valeur("(1 + 2) * 3"), 9);
+        assert_eq!(valeur("((2))"), 2);
+        assert_eq!(valeur("10 - (4 - 3)"), 9);
+        assert_eq!(valeur("(1 | 6) & 3"), 3);
+        let e = expression("(1 + 2").unwrap_err();
+        assert!(e.message.contains("Expected ')'"), "{}", e.message);
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(valeur("1 << 4 + 1"), 32);
+        assert_eq!(valeur("256 >> 2 >> 1"), 32);
+        assert_eq!(valeur("1 << 2 & 0xFF"), 4);
+        assert_eq!(valeur("-16 >> 2"), -4);
+        let e = expression("1 << 64").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Overflow);
+        assert!(e.message.contains("Shift amount out of range"));
+        assert!(expression("1 >> -1").is_err());
+    }
+
+    #[test]
+    fn unary_minus_and_not() {
+        assert_eq!(valeur("-3 * -2"), 6);
+        assert_eq!(valeur("2 - -3"), 5);
+        assert_eq!(valeur("- -5"), 5);
+        assert_eq!(valeur("-(3 - 5)"), 2);
+        assert_eq!(valeur("~0"), -1);
+        assert_eq!(valeur("-2 * 3 + 1"), -5);
+        assert_eq!(valeur("-9223372036854775807 - 1"), i64::MIN);
+        let e = expression("-(-9223372036854775807 - 1)").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let e = expression("5 / 0").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Overflow);
+        assert!(e.message.contains("Division by zero"), "{}", e.message);
+        // L'erreur pointe l'opérateur fautif
+        assert_eq!(e.span, Some(Span::new(0, 1, 3)));
+        let e = expression("1 + 5 % (2 - 2)").unwrap_err();
+        assert!(e.message.contains("Division by zero"));
+        assert_eq!(e.span, Some(Span::new(0, 1, 7)));
+    }
+
+    #[test]
+    fn labels_stay_symbolic() {
+        // Seule la partie constante se replie : 2 * 3 devient 6
+        assert_eq!(
+            expression("fin + 2 * 3").unwrap(),
+            Expression::Binary {
+                op: BinaryOp::Add,
+                left: Box::new(Expression::Identifier("fin".to_string())),
+                right: Box::new(Expression::Number(6)),
+            }
+        );
+        let e = expression("1 + \"texte\"").unwrap_err();
+        assert!(e.message.contains("only combines numbers"));
     }
 }
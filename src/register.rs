@@ -1,4 +1,5 @@
-use crate::ast::Level;
+use crate::ast::{Expression, Level};
+use crate::error::{ThotError, ThotResult};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RegBase {
@@ -40,16 +41,35 @@ fn level_from_prefix(prefix: &str) -> Option<Level> {
     }
 }
 
-pub fn parse_register(name: &str) -> RegSpec {
+pub fn level_prefix(level: Level) -> &'static str {
+    match level {
+        Level::Base => "",
+        Level::Medium => "m",
+        Level::High => "h",
+        Level::Very => "v",
+        Level::Extreme => "e",
+        Level::Zenith => "x",
+    }
+}
+
+// Le même registre, porté au niveau demandé : ("hka", Base) -> "ka"
+fn at_level(name: &str, level: Level) -> String {
+    let base = if name.len() == 3 { &name[1..] } else { name };
+    format!("{}{base}", level_prefix(level))
+}
+
+pub fn parse_register(name: &str) -> ThotResult<RegSpec> {
     let (level, base_name) = match name.len() {
         2 => (Level::Base, name),
         3 => {
             let (prefix, rest) = name.split_at(1);
-            let level = level_from_prefix(prefix)
-                .unwrap_or_else(|| panic!("Unknown register prefix: %{name}"));
+            let level = level_from_prefix(prefix).ok_or_else(|| {
+                ThotError::syntax(format!("Unknown register prefix: %{name}"))
+                    .with_help("valid prefixes are m (16-bit), h (32-bit), v (64-bit), e (128-bit) and x (256-bit)")
+            })?;
             (level, rest)
         }
-        _ => panic!("Unknown register: %{name}"),
+        _ => return Err(ThotError::syntax(format!("Unknown register: %{name}"))),
     };
 
     let kind = match base_name {
@@ -62,21 +82,25 @@ pub fn parse_register(name: &str) -> RegSpec {
         "ds" => RegKind::Segment(SegReg::Ds),
         "es" => RegKind::Segment(SegReg::Es),
         "ss" => RegKind::Segment(SegReg::Ss),
-        _ => panic!("Unknown register: %{name}"),
+        _ => return Err(ThotError::syntax(format!("Unknown register: %{name}"))),
     };
 
     if matches!(kind, RegKind::Segment(_)) && level != Level::Base {
-        panic!("Segment registers cannot be prefixed: %{name}");
+        return Err(ThotError::size_mismatch(format!(
+            "Segment registers cannot be prefixed: %{name}"
+        )));
     }
-    RegSpec { kind, level }
+    Ok(RegSpec { kind, level })
 }
 
-pub fn parse_general_register(name: &str) -> RegSpec {
-    let spec = parse_register(name);
+pub fn parse_general_register(name: &str) -> ThotResult<RegSpec> {
+    let spec = parse_register(name)?;
     if !matches!(spec.kind, RegKind::General(_)) {
-        panic!("Expected a general register, found %{name}");
+        return Err(ThotError::syntax(format!(
+            "Expected a general register, found %{name}"
+        )));
     }
-    spec
+    Ok(spec)
 }
 
 pub fn reg_code(base: RegBase) -> u8 {
@@ -110,22 +134,97 @@ pub fn modrm_mov_reg_rm(dest: RegBase, src: RegBase) -> u8 {
     0xC0 | (reg_code(dest) << 3) | reg_code(src)
 }
 
+/// La taille du déplacement qui suit le ModRM (et le SIB) d'un opérande mémoire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Displacement {
+    None,
+    Byte(i8),
+    Dword,
+}
+
+/// Encode `[base + index*échelle + déplacement]` en adressage 32 bits : ModRM, puis SIB
+/// si un index est présent. `disp` vaut `None` quand le déplacement ne sera connu
+/// qu'après la mise en page (label, variable) : on réserve alors 32 bits.
+pub fn modrm_memory(
+    reg: u8,
+    base: Option<RegBase>,
+    index: Option<(RegBase, u8)>,
+    disp: Option<i64>,
+) -> ThotResult<(Vec<u8>, Displacement)> {
+    // Nos registres n'incluent ni ESP ni EBP : pas de cas particulier pour rm=100/101.
+    let taille = match (base, disp) {
+        (None, _) => Displacement::Dword,
+        (Some(_), Some(0)) => Displacement::None,
+        (Some(_), Some(d)) => match i8::try_from(d) {
+            Ok(petit) => Displacement::Byte(petit),
+            Err(_) => Displacement::Dword,
+        },
+        (Some(_), None) => Displacement::Dword,
+    };
+    let mode: u8 = match (base, taille) {
+        (None, _) | (_, Displacement::None) => 0b00,
+        (_, Displacement::Byte(_)) => 0b01,
+        (_, Displacement::Dword) => 0b10,
+    };
+    let octets = match index {
+        None => {
+            let rm = base.map(reg_code).unwrap_or(0b101); // 101 sans base = disp32 seul
+            vec![(mode << 6) | (reg << 3) | rm]
+        }
+        Some((index, echelle)) => {
+            let facteur = match echelle {
+                1 => 0,
+                2 => 1,
+                4 => 2,
+                8 => 3,
+                _ => {
+                    return Err(ThotError::syntax(format!(
+                        "Invalid index scale {echelle}: expected 1, 2, 4 or 8"
+                    )));
+                }
+            };
+            let sib_base = base.map(reg_code).unwrap_or(0b101); // 101 sans base = disp32
+            vec![
+                (mode << 6) | (reg << 3) | 0b100, // 100 : un SIB suit
+                (facteur << 6) | (reg_code(index) << 3) | sib_base,
+            ]
+        }
+    };
+    Ok((octets, taille))
+}
+
 pub fn ensure_same_level(
     context: &str,
     left: &str,
     left_level: Level,
     right: &str,
     right_level: Level,
-) {
+) -> ThotResult<()> {
     if left_level != right_level {
-        panic!("Size mismatch in {context}: %{left} ({left_level}) vs %{right} ({right_level})",);
+        return Err(ThotError::size_mismatch(format!(
+            "Size mismatch in {context}: %{left} ({left_level}) vs %{right} ({right_level})",
+        ))
+        .with_help(format!(
+            "%{right} is {}-bit, use %{} for {}",
+            right_level.bits(),
+            at_level(right, left_level),
+            left_level.name()
+        )));
     }
+    Ok(())
 }
 
-pub fn ensure_supported_level(context: &str, reg: &str, level: Level) {
+pub fn ensure_supported_level(context: &str, reg: &str, level: Level) -> ThotResult<()> {
     if level > Level::High {
-        panic!("Unsupported register size in {context}: %{reg} ({level})",);
+        return Err(ThotError::size_mismatch(format!(
+            "Unsupported register size in {context}: %{reg} ({level})",
+        ))
+        .with_help(format!(
+            "{context} works up to High, use %{} (32-bit)",
+            at_level(reg, Level::High)
+        )));
     }
+    Ok(())
 }
 
 pub fn channel_max(level: Level) -> u128 {
@@ -137,24 +236,322 @@ pub fn channel_max(level: Level) -> u128 {
     }
 }
 
-pub fn ensure_helix_fits(context: &str, reg: &str, level: Level, ra: u128, apophis: u128) {
+pub fn ensure_helix_fits(
+    context: &str,
+    reg: &str,
+    level: Level,
+    ra: u128,
+    apophis: u128,
+) -> ThotResult<()> {
     let max = channel_max(level);
     if ra > max || apophis > max {
-        panic!(
+        return Err(ThotError::overflow(format!(
             "Overflow in {context} for %{reg} ({level}): ra={ra} apophis={apophis} (max per channel = {max})",
-        );
+        ))
+        .with_help(helix_help(reg, ra.max(apophis))));
     }
+    Ok(())
 }
 
-pub fn ensure_number_fits(context: &str, reg: &str, level: Level, value: i32) {
+// Propose le plus petit niveau dont les forces peuvent contenir la valeur
+fn helix_help(reg: &str, value: u128) -> String {
+    let levels = [Level::High, Level::Very, Level::Extreme, Level::Zenith];
+    match levels.iter().find(|level| channel_max(**level) >= value) {
+        Some(level) => format!(
+            "each channel holds half the register, %{} has {}-bit channels",
+            at_level(reg, *level),
+            level.bits() / 2
+        ),
+        None => "no register has channels wider than 128 bits".to_string(),
+    }
+}
+
+// Une valeur immédiate est acceptée si elle tient dans le registre, lue
+// comme signée (-128) ou non signée (255) : c'est la largeur qui compte.
+pub fn ensure_number_fits(context: &str, reg: &str, level: Level, value: i64) -> ThotResult<()> {
     let bits = level.bits() as u32;
-    if bits >= 32 {
-        return;
+    if bits >= 64 {
+        return Ok(());
     }
-    let max: i128 = (1i128 << (bits - 1)) - 1;
+    let max: i128 = (1i128 << bits) - 1;
     let min: i128 = -(1i128 << (bits - 1));
     let v = value as i128;
     if v < min || v > max {
-        panic!("Overflow in {context} for %{reg} ({level}): value={value} (min={min} max={max})",);
+        return Err(ThotError::overflow(format!(
+            "Overflow in {context} for %{reg} ({level}): value={value} (min={min} max={max})",
+        ))
+        .with_help(number_help(reg, level, v)));
+    }
+    Ok(())
+}
+
+// Propose le plus petit niveau au-dessus de celui qui déborde et qui accepte la valeur
+fn number_help(reg: &str, level: Level, value: i128) -> String {
+    let levels = [Level::Medium, Level::High];
+    let wider = levels.iter().filter(|wider| **wider > level).find(|wider| {
+        let bits = wider.bits() as u32;
+        value >= -(1i128 << (bits - 1)) && value < (1i128 << bits)
+    });
+    match wider {
+        Some(wider) => format!(
+            "use a wider register such as %{} ({}-bit)",
+            at_level(reg, *wider),
+            wider.bits()
+        ),
+        None => "no register takes an immediate wider than 32 bits, beyond that only a Helix (ra:apophis) fits".to_string(),
+    }
+}
+
+// Les immédiats sans registre de destination (push, return, nama) sont encodés sur 32 bits.
+pub fn imm32(context: &str, value: i64) -> ThotResult<u32> {
+    if value < i32::MIN as i64 || value > u32::MAX as i64 {
+        return Err(ThotError::overflow(format!(
+            "Overflow in {context}: value={value} does not fit in 32 bits"
+        )));
+    }
+    Ok(value as u32)
+}
+
+// Les verbes portés par les routines __helix_*128 / __zenith_*256 : tout sauf Very,
+// qu'aucune routine ne sait encore manipuler.
+pub fn ensure_helix_level(context: &str, reg: &str, level: Level) -> ThotResult<()> {
+    if level == Level::Very {
+        return Err(ThotError::size_mismatch(format!(
+            "Unsupported register size in {context}: %{reg} ({level})",
+        ))
+        .with_help(format!(
+            "{context} works up to High, then on Extreme and Zenith: use %{} or %{}",
+            at_level(reg, Level::High),
+            at_level(reg, Level::Extreme)
+        )));
+    }
+    Ok(())
+}
+
+// Un octet n'existe que pour ka, ib, da et ba : en registre 8 bits, les codes de
+// si et di désignent DH et BH.
+pub fn ensure_byte_register(
+    context: &str,
+    reg: &str,
+    level: Level,
+    base: RegBase,
+) -> ThotResult<()> {
+    if level == Level::Base && matches!(base, RegBase::Si | RegBase::Di) {
+        return Err(
+            ThotError::size_mismatch(format!("'{context}' has no 8-bit form for %{reg}"))
+                .with_help(format!(
+                    "%{reg} has no low byte, use %{} (16-bit)",
+                    at_level(reg, Level::Medium)
+                )),
+        );
+    }
+    Ok(())
+}
+
+// Le compte d'un décalage : un nombre plus petit que la largeur décalée (le registre
+// jusqu'à High, chaque canal au-delà), ou %da dont la valeur n'est connue qu'à l'exécution.
+pub fn ensure_shift_count(
+    context: &str,
+    reg: &str,
+    level: Level,
+    count: &Expression,
+) -> ThotResult<()> {
+    let (largeur, tenue) = if level <= Level::High {
+        (level.bits() as i64, format!("%{reg} holds"))
+    } else {
+        (
+            (level.bits() / 2) as i64,
+            format!("each channel of %{reg} holds"),
+        )
+    };
+    match count {
+        Expression::Number(n) if !(0..largeur).contains(n) => Err(ThotError::overflow(format!(
+            "Overflow in {context} for %{reg} ({level}): count={n} (max = {})",
+            largeur - 1
+        ))
+        .with_help(format!("{tenue} {largeur} bits"))),
+        Expression::Register(r) => {
+            let spec = parse_general_register(r)?;
+            if spec.kind != RegKind::General(RegBase::Da) || spec.level != Level::Base {
+                return Err(ThotError::syntax(format!(
+                    "'{context}' only takes its count from %da, found %{r}"
+                ))
+                .with_help("move the count into %da first"));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+// La division (wep, wep_ankh) suit x86 : le dividende est %ka, qui reçoit le quotient,
+// et le reste va dans %da du même niveau. Le diviseur ne peut donc être ni l'un ni l'autre,
+// et un diviseur constant nul (ou un canal nul d'un Helix) est refusé.
+pub fn ensure_division(
+    context: &str,
+    destination: &str,
+    level: Level,
+    value: &Expression,
+) -> ThotResult<()> {
+    let dest_spec = parse_general_register(destination)?;
+    if dest_spec.kind != RegKind::General(RegBase::Ka) {
+        return Err(ThotError::syntax(format!(
+            "'{context}' divides %ka, found %{destination}"
+        ))
+        .with_help(format!(
+            "move the dividend into %{}: the quotient stays there and the remainder goes to %{}",
+            at_level("ka", level),
+            at_level("da", level)
+        )));
+    }
+    ensure_helix_level(context, destination, level)?;
+    let zero = || {
+        ThotError::overflow(format!("Division by zero in {context}"))
+            .with_help("a Helix division by zero at runtime saturates, a constant one is a mistake")
+    };
+    match value {
+        Expression::Number(0) => return Err(zero()),
+        Expression::Number(n) if level <= Level::High => {
+            ensure_number_fits(context, destination, level, *n)?
+        }
+        Expression::Helix { ra, apophis } => {
+            ensure_helix_fits(context, destination, level, *ra, *apophis)?;
+            // Jusqu'à High, le Helix n'est qu'un nombre ; au-delà, chaque canal divise
+            let nul = if level <= Level::High {
+                *ra == 0 && *apophis == 0
+            } else {
+                *ra == 0 || *apophis == 0
+            };
+            if nul {
+                return Err(zero());
+            }
+        }
+        Expression::Register(src) => {
+            let src_spec = parse_general_register(src)?;
+            ensure_same_level(context, destination, level, src, src_spec.level)?;
+            if let RegKind::General(base @ (RegBase::Ka | RegBase::Da)) = src_spec.kind {
+                let role = if base == RegBase::Ka {
+                    "dividend"
+                } else {
+                    "remainder"
+                };
+                return Err(ThotError::syntax(format!(
+                    "'{context}' cannot divide by %{src}: it holds the {role}"
+                ))
+                .with_help(if level == Level::Base {
+                    // si et di n'ont pas d'octet
+                    "move the divisor into %ba or %ib".to_string()
+                } else {
+                    format!(
+                        "move the divisor into %{}, %{}, %{} or %{}",
+                        at_level("ba", level),
+                        at_level("ib", level),
+                        at_level("si", level),
+                        at_level("di", level)
+                    )
+                }));
+            }
+        }
+        _ if level > Level::High => {
+            return Err(ThotError::syntax(format!(
+                "'{context}' for Extreme and Zenith registers only accepts Helix literals or registers."
+            )));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ModRM, SIB et déplacement, tels que l'Émetteur les écrit
+    fn encode(reg: u8, base: Option<RegBase>, index: Option<(RegBase, u8)>, disp: i64) -> Vec<u8> {
+        let (mut octets, taille) = modrm_memory(reg, base, index, Some(disp)).unwrap();
+        match taille {
+            Displacement::None => {}
+            Displacement::Byte(d) => octets.push(d as u8),
+            Displacement::Dword => octets.extend_from_slice(&(disp as i32).to_le_bytes()),
+        }
+        octets
+    }
+
+    #[test]
+    fn base_only() {
+        use RegBase::*;
+        // [ebx] : mod 00, pas de déplacement
+        assert_eq!(encode(0, Some(Ba), None, 0), [0x03]);
+        // [esi + 8] : disp8
+        assert_eq!(encode(1, Some(Si), None, 8), [0x4E, 0x08]);
+        // [edi - 8] : disp8 négatif
+        assert_eq!(encode(0, Some(Di), None, -8), [0x47, 0xF8]);
+        // [ebx + 128] et [ebx - 129] : hors d'un i8, disp32
+        assert_eq!(encode(0, Some(Ba), None, 128), [0x83, 0x80, 0, 0, 0]);
+        assert_eq!(
+            encode(2, Some(Ba), None, -129),
+            [0x93, 0x7F, 0xFF, 0xFF, 0xFF]
+        );
+        assert_eq!(encode(0, Some(Ka), None, -128), [0x40, 0x80]);
+    }
+
+    #[test]
+    fn absolute_address() {
+        // [0x9000] : rm 101 sans base, toujours disp32 (même pour 0)
+        assert_eq!(encode(0, None, None, 0x9000), [0x05, 0x00, 0x90, 0, 0]);
+        assert_eq!(encode(3, None, None, 0), [0x1D, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn scaled_index() {
+        use RegBase::*;
+        // [ebx + esi*échelle] : rm 100, puis SIB ss|index|base
+        for (echelle, ss) in [(1, 0x00), (2, 0x40), (4, 0x80), (8, 0xC0)] {
+            assert_eq!(
+                encode(0, Some(Ba), Some((Si, echelle)), 0),
+                [0x04, ss | 0x33],
+                "échelle {echelle}"
+            );
+        }
+        // [eax + ecx*4 - 4] : SIB puis disp8 négatif
+        assert_eq!(encode(2, Some(Ka), Some((Ib, 4)), -4), [0x54, 0x88, 0xFC]);
+        // [edx + edi*2 + 0x1000] : SIB puis disp32
+        assert_eq!(
+            encode(0, Some(Da), Some((Di, 2)), 0x1000),
+            [0x84, 0x7A, 0x00, 0x10, 0, 0]
+        );
+        // [esi*8 + 0x20] sans base : SIB base 101, mod 00, disp32 obligatoire
+        assert_eq!(
+            encode(1, None, Some((Si, 8)), 0x20),
+            [0x0C, 0xF5, 0x20, 0, 0, 0]
+        );
+        assert_eq!(
+            encode(1, None, Some((Si, 1)), -1),
+            [0x0C, 0x35, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+    }
+
+    #[test]
+    fn late_displacement_reserves_32_bits() {
+        // Un label : la valeur n'arrive qu'après la mise en page
+        let (octets, taille) = modrm_memory(0, Some(RegBase::Ba), None, None).unwrap();
+        assert_eq!((octets, taille), (vec![0x83], Displacement::Dword));
+    }
+
+    #[test]
+    fn invalid_scale() {
+        let e = modrm_memory(0, Some(RegBase::Ba), Some((RegBase::Si, 3)), Some(0)).unwrap_err();
+        assert!(e.message.contains("Invalid index scale 3"));
+    }
+
+    #[test]
+    fn no_base_needs_the_esp_or_ebp_forms() {
+        // modrm_memory n'a pas de cas pour ESP (rm 100 : un SIB obligatoire) ni pour EBP
+        // (mod 00 rm 101 : disp32 sans base, il faut un disp8 nul). Un registre qui
+        // s'encoderait 4 ou 5 doit d'abord y être traité.
+        use RegBase::*;
+        for base in [Ka, Ib, Da, Ba, Si, Di] {
+            assert!(!matches!(reg_code(base), 4 | 5), "{base:?}");
+        }
     }
 }
@@ -0,0 +1,77 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Position d'un jeton ou d'une instruction : quelle tablette, quelle ligne, quelle colonne.
+/// Les lignes et colonnes commencent à 1, comme dans un éditeur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub file: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(file: usize, line: usize, column: usize) -> Self {
+        Span { file, line, column }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Une valeur (jeton ou instruction) accompagnée de sa position d'origine.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Spanned { node, span }
+    }
+}
+
+/// Une tablette chargée par le Scribe : son chemin et son texte.
+#[derive(Debug, Clone)]
+pub struct Tablet {
+    pub path: PathBuf,
+    pub source: String,
+}
+
+/// Registre de toutes les tablettes lues pendant une compilation.
+/// L'identifiant de fichier d'un `Span` est l'index dans ce registre, ce qui
+/// permet de retrouver la tablette d'origine même après l'aplatissement des `dema`.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    tablets: Vec<Tablet>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    pub fn add(&mut self, path: &Path, source: String) -> usize {
+        self.tablets.push(Tablet {
+            path: path.to_path_buf(),
+            source,
+        });
+        self.tablets.len() - 1
+    }
+
+    pub fn get(&self, file: usize) -> Option<&Tablet> {
+        self.tablets.get(file)
+    }
+
+    /// Formate une position complète `chemin:ligne:colonne`.
+    pub fn locate(&self, span: Span) -> String {
+        match self.get(span.file) {
+            Some(tablet) => format!("{}:{}", tablet.path.display(), span),
+            None => span.to_string(),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+/// La version de la bibliothèque standard, visible dans chaque tablette
+/// sous le nom `THOT_STD_VERSION` (pour `if THOT_STD_VERSION >= 2`).
+pub const VERSION: i64 = 1;
+
+/// Les tablettes de la bibliothèque standard, embarquées dans Thot.
+/// Elles se tissent avec `dema <std/nom>`, sans rien installer à côté du compilateur.
+const TABLETTES: [(&str, &str); 5] = [
+    ("delay", include_str!("../std/delay.maat")),
+    ("format", include_str!("../std/format.maat")),
+    ("keyboard", include_str!("../std/keyboard.maat")),
+    ("memory", include_str!("../std/memory.maat")),
+    ("print", include_str!("../std/print.maat")),
+];
+
+const PREFIXE: &str = "std/";
+
+/// `dema <std/...>` vise la bibliothèque standard, qu'elle contienne la tablette ou non.
+pub fn is_std(path: &str) -> bool {
+    path.starts_with(PREFIXE)
+}
+
+/// Le code de `std/nom` (avec ou sans `.maat`).
+pub fn tablette(path: &str) -> Option<&'static str> {
+    let nom = path.strip_prefix(PREFIXE)?;
+    let nom = nom.strip_suffix(".maat").unwrap_or(nom);
+    TABLETTES
+        .iter()
+        .find(|(tablette, _)| *tablette == nom)
+        .map(|(_, code)| *code)
+}
+
+/// Le chemin affiché dans les diagnostics : il n'existe pas sur le disque.
+pub fn chemin(path: &str) -> PathBuf {
+    let nom = path.strip_prefix(PREFIXE).unwrap_or(path);
+    let nom = nom.strip_suffix(".maat").unwrap_or(nom);
+    PathBuf::from(format!("<std>/{nom}.maat"))
+}
+
+pub fn noms() -> Vec<String> {
+    TABLETTES
+        .iter()
+        .map(|(nom, _)| format!("{PREFIXE}{nom}"))
+        .collect()
+}
+
+/// L'empreinte de toute la bibliothèque : une tablette modifiée invalide le cache.
+pub fn empreinte() -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new();
+    for (nom, code) in TABLETTES {
+        hasher.update(nom.as_bytes());
+        hasher.update(&(code.len() as u64).to_le_bytes());
+        hasher.update(code.as_bytes());
+    }
+    hasher.finalize()
+}
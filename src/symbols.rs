@@ -0,0 +1,128 @@
+use crate::ast::{Expression, Instruction};
+use crate::emitter::RUNTIME_ROUTINES;
+use crate::error::ThotError;
+use crate::eval::{identifiers, substitute};
+use crate::source::{Span, Spanned};
+use std::collections::{HashMap, HashSet};
+
+/// La table des symboles : on relève toutes les constantes `smen` du programme aplati,
+/// puis on les substitue partout. Une constante peut donc servir avant sa définition,
+/// ou dans une autre tablette que la sienne.
+pub fn resolve_constants(instructions: &mut [Spanned<Instruction>]) -> Result<(), Vec<ThotError>> {
+    // 1. Collecte des définitions
+    let mut en_attente: Vec<(String, Expression, Span)> = instructions
+        .iter()
+        .filter_map(|instruction| match &instruction.node {
+            Instruction::Smen { nom, valeur } => {
+                Some((nom.clone(), valeur.clone(), instruction.span))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // 2. Point fixe : chaque tour résout les constantes dont les dépendances sont connues
+    let mut constants: HashMap<String, i64> = HashMap::new();
+    let mut erreurs = Vec::new();
+    loop {
+        let mut progres = false;
+        en_attente.retain_mut(|(nom, valeur, span)| match substitute(valeur, &constants) {
+            Ok(Expression::Number(n)) => {
+                constants.insert(nom.clone(), n);
+                progres = true;
+                false
+            }
+            Ok(partiel) => {
+                *valeur = partiel;
+                true
+            }
+            Err(erreur) => {
+                erreurs.push(erreur.at(*span));
+                progres = true;
+                false
+            }
+        });
+        if !progres {
+            break;
+        }
+    }
+
+    // 3. Ce qui reste dépend d'un label, de '$', ou de soi-même
+    let restants: HashSet<&str> = en_attente.iter().map(|(nom, ..)| nom.as_str()).collect();
+    for (nom, valeur, span) in &en_attente {
+        let noms = identifiers(valeur);
+        let erreur = match noms.iter().find(|n| !restants.contains(*n)) {
+            Some(inconnu) => ThotError::syntax(format!(
+                "Smen '{nom}' requires a fixed numerical value, but '{inconnu}' is not a constant"
+            ))
+            .with_help("labels and variables only get an address after layout"),
+            None if noms.is_empty() => ThotError::syntax(format!(
+                "Smen '{nom}' cannot use '$': the current address is not a constant"
+            )),
+            None => ThotError::syntax(format!(
+                "Smen '{nom}' has a circular definition ({})",
+                noms.join(", ")
+            )),
+        };
+        erreurs.push(erreur.at(*span));
+    }
+    if !erreurs.is_empty() {
+        return Err(erreurs);
+    }
+
+    // 4. Substitution dans tout le programme
+    for instruction in instructions.iter_mut() {
+        let span = instruction.span;
+        for expression in instruction.node.expressions_mut() {
+            match substitute(expression, &constants) {
+                Ok(resolue) => *expression = resolue,
+                Err(erreur) => erreurs.push(erreur.at(span)),
+            }
+        }
+    }
+    if erreurs.is_empty() {
+        Ok(())
+    } else {
+        Err(erreurs)
+    }
+}
+
+/// Labels, variables `nama` et constantes `smen` partagent un seul espace de noms :
+/// un nom défini deux fois est une erreur (avec les deux positions), et les noms
+/// des routines de Thot ('__' et `RUNTIME_ROUTINES`) sont réservés.
+pub fn check_definitions(instructions: &[Spanned<Instruction>]) -> Result<(), Vec<ThotError>> {
+    let mut definis: HashMap<&str, Span> = HashMap::new();
+    let mut erreurs = Vec::new();
+    for instruction in instructions {
+        let (nom, genre) = match &instruction.node {
+            Instruction::Label(nom) => (nom, "Label"),
+            Instruction::Nama { name, .. } => (name, "Variable"),
+            Instruction::Smen { nom, .. } => (nom, "Smen"),
+            _ => continue,
+        };
+        if nom.starts_with("__") || RUNTIME_ROUTINES.contains(&nom.as_str()) {
+            erreurs.push(
+                ThotError::syntax(format!(
+                    "{genre} '{nom}' uses a name reserved for the routines of Thot"
+                ))
+                .at(instruction.span)
+                .with_help("names starting with '__', 'std_print' and 'print_hex_32' are reserved"),
+            );
+            continue;
+        }
+        match definis.get(nom.as_str()) {
+            Some(premier) => erreurs.push(
+                ThotError::syntax(format!("{genre} '{nom}' is defined twice"))
+                    .at(instruction.span)
+                    .with_note("first defined here", *premier),
+            ),
+            None => {
+                definis.insert(nom, instruction.span);
+            }
+        }
+    }
+    if erreurs.is_empty() {
+        Ok(())
+    } else {
+        Err(erreurs)
+    }
+}
@@ -0,0 +1,97 @@
+// pesh, maa, kem, hem et les décalages : jusqu'à High, chaque niveau a sa largeur
+// d'opérande ; Extreme et Zenith appellent une routine, posée seulement si elle sert.
+mod common;
+
+use common::{atelier, deux_modes, emet, noyau, octets, stderr, thot};
+
+// (code, Mode Réel, Mode Protégé)
+const NIVEAUX: [(&str, &[u8], &[u8]); 18] = [
+    ("pesh %ka, %ib", &[0x30, 0xC8], &[0x30, 0xC8]),
+    ("pesh %mka, %mib", &[0x31, 0xC8], &[0x66, 0x31, 0xC8]),
+    ("pesh %hka, %hib", &[0x66, 0x31, 0xC8], &[0x31, 0xC8]),
+    ("pesh %ka, 0x0F", &[0x80, 0xF0, 0x0F], &[0x80, 0xF0, 0x0F]),
+    (
+        "pesh %mka, 0x1234",
+        &[0x81, 0xF0, 0x34, 0x12],
+        &[0x66, 0x81, 0xF0, 0x34, 0x12],
+    ),
+    ("maa %ka, 0x80", &[0xF6, 0xC0, 0x80], &[0xF6, 0xC0, 0x80]),
+    ("maa %mka, %mda", &[0x85, 0xD0], &[0x66, 0x85, 0xD0]),
+    (
+        "maa %hka, 1",
+        &[0x66, 0xF7, 0xC0, 1, 0, 0, 0],
+        &[0xF7, 0xC0, 1, 0, 0, 0],
+    ),
+    ("kem %ka", &[0xF6, 0xD0], &[0xF6, 0xD0]),
+    ("hem %mka", &[0xF7, 0xD8], &[0x66, 0xF7, 0xD8]),
+    ("kem %hka", &[0x66, 0xF7, 0xD0], &[0xF7, 0xD0]),
+    ("nedjes %ka, 1", &[0xD0, 0xE8], &[0xD0, 0xE8]),
+    ("wer %ba, 2", &[0xC0, 0xE3, 2], &[0xC0, 0xE3, 2]),
+    ("deben %mka, 3", &[0xC1, 0xC0, 3], &[0x66, 0xC1, 0xC0, 3]),
+    ("nebed %hib, 4", &[0x66, 0xC1, 0xC9, 4], &[0xC1, 0xC9, 4]),
+    ("nedjes_ankh %mka, 1", &[0xD1, 0xF8], &[0x66, 0xD1, 0xF8]),
+    // Le compte passe par CL : ECX et EDX s'échangent autour du décalage
+    (
+        "nedjes_ankh %ka, %da",
+        &[0x66, 0x87, 0xD1, 0xD2, 0xF8, 0x66, 0x87, 0xD1],
+        &[0x87, 0xD1, 0xD2, 0xF8, 0x87, 0xD1],
+    ),
+    (
+        "wer %hka, %da",
+        &[0x66, 0x87, 0xD1, 0x66, 0xD3, 0xE0, 0x66, 0x87, 0xD1],
+        &[0x87, 0xD1, 0xD3, 0xE0, 0x87, 0xD1],
+    ),
+];
+
+#[test]
+fn operand_width_follows_the_level() {
+    for (i, (code, reel, protege)) in NIVEAUX.into_iter().enumerate() {
+        let emis = deux_modes(&format!("largeur-{i}"), code);
+        assert_eq!(emis, (reel.to_vec(), protege.to_vec()), "{code}");
+    }
+}
+
+#[test]
+fn si_and_di_have_no_byte_form() {
+    for code in ["nedjes %si, 1", "maa %ka, %di", "kem %di"] {
+        let erreur = octets("octet", code, true).unwrap_err();
+        assert!(erreur.contains("has no 8-bit form"), "{code}: {erreur}");
+        assert!(erreur.contains("(16-bit)"), "{code}: {erreur}");
+    }
+}
+
+// Le début de chaque routine Extreme (PUSHAD puis le premier dword de son canal)
+const XOR128: &[u8] = &[0x60, 0x8B, 0x47, 0x00, 0x33, 0x46, 0x00];
+const TEST128: &[u8] = &[0x60, 0x31, 0xD2, 0x8B, 0x47, 0x00, 0x23, 0x46, 0x00];
+const SHR128: &[u8] = &[0x60, 0x83, 0xE1, 0x3F, 0xE3];
+
+#[test]
+fn helix_routines_are_emitted_on_demand() {
+    let dossier = atelier("routines");
+    let sortie = thot(&dossier, &noyau("", ""), &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    for routine in [XOR128, TEST128, SHR128] {
+        assert!(!emet(&dossier, routine), "{routine:02X?}");
+    }
+
+    let sortie = thot(&dossier, &noyau("    pesh %eka, %eib", ""), &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    assert!(emet(&dossier, XOR128));
+    assert!(!emet(&dossier, TEST128));
+    assert!(!emet(&dossier, SHR128));
+
+    // Deux appels, une seule copie
+    let sortie = thot(
+        &dossier,
+        &noyau("    nedjes %eka, 3\n    nedjes %eib, %da", ""),
+        &[],
+    );
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    let binaire = std::fs::read(dossier.join("main.bin")).unwrap();
+    let copies = binaire
+        .windows(SHR128.len())
+        .filter(|w| *w == SHR128)
+        .count();
+    assert_eq!(copies, 1);
+    assert!(!emet(&dossier, XOR128));
+}
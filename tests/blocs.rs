@@ -0,0 +1,39 @@
+// tut, meh et sia : CLD, REP (REPE pour sia) et l'opcode de la granularité. En Mode Réel,
+// 0x67 fait compter ECX ; 0x66 bascule entre mot et dword selon le mode.
+mod common;
+
+use common::deux_modes;
+
+// (code, Mode Réel, Mode Protégé)
+const BLOCS: [(&str, &[u8], &[u8]); 9] = [
+    ("tut", &[0xFC, 0x67, 0xF3, 0xA4], &[0xFC, 0xF3, 0xA4]),
+    ("mtut", &[0xFC, 0x67, 0xF3, 0xA5], &[0xFC, 0x66, 0xF3, 0xA5]),
+    ("htut", &[0xFC, 0x66, 0x67, 0xF3, 0xA5], &[0xFC, 0xF3, 0xA5]),
+    ("meh", &[0xFC, 0x67, 0xF3, 0xAA], &[0xFC, 0xF3, 0xAA]),
+    ("mmeh", &[0xFC, 0x67, 0xF3, 0xAB], &[0xFC, 0x66, 0xF3, 0xAB]),
+    ("hmeh", &[0xFC, 0x66, 0x67, 0xF3, 0xAB], &[0xFC, 0xF3, 0xAB]),
+    // TEST ECX, ECX d'abord : un bloc vide est égal, REPE CMPS ne toucherait pas ZF
+    (
+        "sia",
+        &[0x66, 0x85, 0xC9, 0xFC, 0x67, 0xF3, 0xA6],
+        &[0x85, 0xC9, 0xFC, 0xF3, 0xA6],
+    ),
+    (
+        "msia",
+        &[0x66, 0x85, 0xC9, 0xFC, 0x67, 0xF3, 0xA7],
+        &[0x85, 0xC9, 0xFC, 0x66, 0xF3, 0xA7],
+    ),
+    (
+        "hsia",
+        &[0x66, 0x85, 0xC9, 0xFC, 0x66, 0x67, 0xF3, 0xA7],
+        &[0x85, 0xC9, 0xFC, 0xF3, 0xA7],
+    ),
+];
+
+#[test]
+fn granularity_and_mode() {
+    for (code, reel, protege) in BLOCS {
+        let emis = deux_modes(&format!("bloc-{code}"), code);
+        assert_eq!(emis, (reel.to_vec(), protege.to_vec()), "{code}");
+    }
+}
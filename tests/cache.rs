@@ -0,0 +1,50 @@
+// Le cache blake3 et le fichier .d : une tablette réécrite à l'identique ne relance
+// pas la compilation, une tablette modifiée si.
+mod common;
+
+use common::{atelier, emet, stderr, thot};
+use std::fs;
+
+const PRINCIPAL: &str = "dema \"dep\"\n";
+const OPTIONS: &[&str] = &["--cache", "cache", "--dep-file", "main.d"];
+const DEPFILE: &str = "main.bin: \\\n  main.maat \\\n  dep.maat\n\nmain.maat:\n\ndep.maat:\n";
+
+fn stdout(sortie: &std::process::Output) -> String {
+    String::from_utf8_lossy(&sortie.stdout).into_owned()
+}
+
+#[test]
+fn cache_follows_the_content_of_dependencies() {
+    let dossier = atelier("cache");
+    fs::write(dossier.join("dep.maat"), "sesh 0xDE, 0xAD, 0x01\n").unwrap();
+    let sortie = thot(&dossier, PRINCIPAL, OPTIONS);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    assert!(stdout(&sortie).contains("has been compiled successfully"));
+    assert_eq!(fs::read_to_string(dossier.join("main.d")).unwrap(), DEPFILE);
+
+    // Réécrite à l'identique : seule la date change, le binaire vient du cache
+    fs::write(dossier.join("dep.maat"), "sesh 0xDE, 0xAD, 0x01\n").unwrap();
+    fs::remove_file(dossier.join("main.bin")).unwrap();
+    fs::remove_file(dossier.join("main.d")).unwrap();
+    let sortie = thot(&dossier, PRINCIPAL, OPTIONS);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    assert!(
+        stdout(&sortie).contains("is up to date"),
+        "{}",
+        stdout(&sortie)
+    );
+    assert!(!stdout(&sortie).contains("has been compiled successfully"));
+    assert!(emet(&dossier, &[0xDE, 0xAD, 0x01]));
+    // Le fichier .d est réécrit même quand le binaire vient du cache
+    assert_eq!(fs::read_to_string(dossier.join("main.d")).unwrap(), DEPFILE);
+
+    // Un octet de plus dans la dépendance : la compilation reprend
+    fs::write(dossier.join("dep.maat"), "sesh 0xDE, 0xAD, 0x02\n").unwrap();
+    let sortie = thot(&dossier, PRINCIPAL, OPTIONS);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    assert!(!stdout(&sortie).contains("is up to date"));
+    assert!(stdout(&sortie).contains("has been compiled successfully"));
+    assert!(emet(&dossier, &[0xDE, 0xAD, 0x02]));
+    assert!(!emet(&dossier, &[0xDE, 0xAD, 0x01]));
+    assert_eq!(fs::read_to_string(dossier.join("main.d")).unwrap(), DEPFILE);
+}
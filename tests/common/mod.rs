@@ -0,0 +1,91 @@
+// Les outils partagés des tests d'intégration : chaque test lance le binaire thot
+// dans son propre dossier, comme un utilisateur.
+// Chaque fichier de tests n'en emploie qu'une partie.
+#![allow(dead_code)]
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// Un dossier de travail propre à chaque test, effacé quand le test se termine
+pub struct Atelier(PathBuf);
+
+impl Deref for Atelier {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Atelier {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn atelier(nom: &str) -> Atelier {
+    let dossier = std::env::temp_dir().join(format!("thot-test-{}-{nom}", std::process::id()));
+    let _ = fs::remove_dir_all(&dossier);
+    fs::create_dir_all(&dossier).unwrap();
+    Atelier(dossier)
+}
+
+pub fn thot(dossier: &Path, code: &str, options: &[&str]) -> Output {
+    fs::write(dossier.join("main.maat"), code).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_thot"))
+        .current_dir(dossier)
+        .args(["main.maat", "main.bin", "true"])
+        .args(options)
+        .env_remove("THOT_PATH")
+        .output()
+        .unwrap()
+}
+
+pub fn stderr(sortie: &Output) -> String {
+    String::from_utf8_lossy(&sortie.stderr).into_owned()
+}
+
+// Le binaire produit contient-il cette suite d'octets ?
+pub fn emet(dossier: &Path, octets: &[u8]) -> bool {
+    let binaire = fs::read(dossier.join("main.bin")).unwrap();
+    binaire.windows(octets.len()).any(|w| w == octets)
+}
+
+// Le noyau minimal : les rituels de std n'existent qu'en mode protégé
+pub fn noyau(appels: &str, demas: &str) -> String {
+    format!("jena noyau\nnoyau:\n{appels}\nfin:\n    neheh fin\n{demas}\n")
+}
+
+// Deux bornes de données encadrent le code observé : ses octets sont ceux d'entre elles
+const DEBUT: [u8; 4] = [0xF1, 0xF2, 0xF3, 0xF4];
+const FIN: [u8; 4] = [0xF4, 0xF3, 0xF2, 0xF1];
+
+// Les octets émis par `code`, en Mode Réel (Stage 1) ou en Mode Protégé (dans le noyau),
+// ou le message d'erreur du compilateur
+pub fn octets(nom: &str, code: &str, protege: bool) -> Result<Vec<u8>, String> {
+    let dossier = atelier(nom);
+    let borne = |b: [u8; 4]| format!("    sesh {}", b.map(|o| format!("{o:#04X}")).join(", "));
+    let observe = format!("{}\n{code}\n{}", borne(DEBUT), borne(FIN));
+    let source = if protege {
+        noyau(&observe, "")
+    } else {
+        format!("{observe}\n")
+    };
+    let sortie = thot(&dossier, &source, &[]);
+    if !sortie.status.success() {
+        return Err(stderr(&sortie));
+    }
+    let binaire = fs::read(dossier.join("main.bin")).unwrap();
+    let debut = binaire.windows(4).position(|w| w == DEBUT).unwrap() + 4;
+    let fin = debut + binaire[debut..].windows(4).position(|w| w == FIN).unwrap();
+    Ok(binaire[debut..fin].to_vec())
+}
+
+// Les octets de `code` dans les deux modes : (Mode Réel, Mode Protégé)
+pub fn deux_modes(nom: &str, code: &str) -> (Vec<u8>, Vec<u8>) {
+    let reel = octets(&format!("{nom}-reel"), code, false).unwrap();
+    let protege = octets(&format!("{nom}-protege"), code, true).unwrap();
+    (reel, protege)
+}
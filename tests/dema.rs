@@ -0,0 +1,63 @@
+// Le Tisserand : chaque tablette n'est tissée qu'une fois, et un cycle est signalé
+// avec la chaîne d'inclusion.
+mod common;
+
+use common::{atelier, emet, stderr, thot};
+use std::fs;
+
+#[test]
+fn diamond_is_woven_once() {
+    // main -> gauche -> socle, main -> droite -> socle
+    let dossier = atelier("losange");
+    fs::write(dossier.join("gauche.maat"), "dema \"socle\"\n").unwrap();
+    fs::write(dossier.join("droite.maat"), "dema \"socle\"\n").unwrap();
+    fs::write(
+        dossier.join("socle.maat"),
+        "socle:\n    sesh 0xAB, 0xCD, 0xEF\n",
+    )
+    .unwrap();
+    let sortie = thot(&dossier, "dema \"gauche\"\ndema \"droite\"\n", &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+
+    // Tissé deux fois, 'socle:' serait défini deux fois et ses octets doublés
+    assert!(emet(&dossier, &[0xAB, 0xCD, 0xEF]));
+    let binaire = fs::read(dossier.join("main.bin")).unwrap();
+    let copies = binaire
+        .windows(3)
+        .filter(|w| *w == [0xAB, 0xCD, 0xEF])
+        .count();
+    assert_eq!(copies, 1);
+    let annonces = String::from_utf8_lossy(&sortie.stdout)
+        .matches("socle")
+        .count();
+    assert_eq!(annonces, 1);
+}
+
+#[test]
+fn cycle_shows_the_include_stack() {
+    // main -> aller -> main
+    let dossier = atelier("cycle");
+    fs::write(dossier.join("aller.maat"), "wab\ndema \"main\"\n").unwrap();
+    let sortie = thot(&dossier, "dema \"aller\"\n", &[]);
+    assert!(!sortie.status.success());
+    let message = stderr(&sortie);
+    assert!(
+        message.contains("Circular dema: main.maat -> aller.maat -> main.maat"),
+        "{message}"
+    );
+    // L'erreur pointe le dema qui revient, la note celui qui a ouvert le cycle
+    assert!(message.contains("--> aller.maat:2:1"), "{message}");
+    assert!(
+        message.contains("note: the cycle starts when aller.maat is woven here"),
+        "{message}"
+    );
+    assert!(message.contains("--> main.maat:1:1"), "{message}");
+}
+
+#[test]
+fn self_include_is_a_cycle() {
+    let dossier = atelier("soi");
+    let sortie = thot(&dossier, "dema \"main\"\n", &[]);
+    assert!(!sortie.status.success());
+    assert!(stderr(&sortie).contains("Circular dema: main.maat -> main.maat"));
+}
@@ -0,0 +1,113 @@
+// wep et wep_ankh : jusqu'à High, le dividende est %ka étendu à sa propre largeur ;
+// Extreme et Zenith appellent une division longue, posée seulement si elle sert.
+mod common;
+
+use common::{atelier, deux_modes, emet, noyau, octets, stderr, thot};
+
+// Un diviseur registre nul sature : TEST, puis %da garde le dividende et %ka prend tous ses bits
+const OCTET: [u8; 16] = [
+    0x84, 0xC9, 0x75, 0x06, 0x88, 0xC2, 0xB0, 0xFF, 0xEB, 0x06, // TEST CL, CL ... JMP fin
+    0x30, 0xE4, 0xF6, 0xF1, 0x88, 0xE2, // XOR AH, AH ; DIV CL ; MOV DL, AH
+];
+
+// (code, Mode Réel, Mode Protégé)
+const NIVEAUX: [(&str, &[u8], &[u8]); 6] = [
+    ("wep %ka, %ib", &OCTET, &OCTET),
+    (
+        "wep_ankh %ka, 10",
+        // PUSH ECX ; MOV ECX, 10 ; CBW ; IDIV CL ; MOV DL, AH ; POP ECX
+        &[
+            0x66, 0x51, 0x66, 0xB9, 10, 0, 0, 0, 0x98, 0xF6, 0xF9, 0x88, 0xE2, 0x66, 0x59,
+        ],
+        &[
+            0x51, 0xB9, 10, 0, 0, 0, 0x66, 0x98, 0xF6, 0xF9, 0x88, 0xE2, 0x59,
+        ],
+    ),
+    (
+        "wep %mka, %mib",
+        &[
+            0x85, 0xC9, 0x75, 0x07, 0x89, 0xC2, 0x83, 0xC8, 0xFF, 0xEB, 0x04, 0x31, 0xD2, 0xF7,
+            0xF1,
+        ],
+        &[
+            0x66, 0x85, 0xC9, 0x75, 0x09, 0x66, 0x89, 0xC2, 0x66, 0x83, 0xC8, 0xFF, 0xEB, 0x06,
+            0x66, 0x31, 0xD2, 0x66, 0xF7, 0xF1,
+        ],
+    ),
+    (
+        "wep_ankh %mka, 7",
+        // PUSH ECX ; MOV ECX, 7 ; CWD ; IDIV CX ; POP ECX
+        &[
+            0x66, 0x51, 0x66, 0xB9, 7, 0, 0, 0, 0x99, 0xF7, 0xF9, 0x66, 0x59,
+        ],
+        &[0x51, 0xB9, 7, 0, 0, 0, 0x66, 0x99, 0x66, 0xF7, 0xF9, 0x59],
+    ),
+    (
+        "wep %hka, %hsi",
+        &[
+            0x66, 0x85, 0xF6, 0x75, 0x09, 0x66, 0x89, 0xC2, 0x66, 0x83, 0xC8, 0xFF, 0xEB, 0x06,
+            0x66, 0x31, 0xD2, 0x66, 0xF7, 0xF6,
+        ],
+        &[
+            0x85, 0xF6, 0x75, 0x07, 0x89, 0xC2, 0x83, 0xC8, 0xFF, 0xEB, 0x04, 0x31, 0xD2, 0xF7,
+            0xF6,
+        ],
+    ),
+    (
+        "wep_ankh %hka, -3",
+        // PUSH ECX ; MOV ECX, -3 ; CDQ ; IDIV ECX ; POP ECX
+        &[
+            0x66, 0x51, 0x66, 0xB9, 0xFD, 0xFF, 0xFF, 0xFF, 0x66, 0x99, 0x66, 0xF7, 0xF9, 0x66,
+            0x59,
+        ],
+        &[0x51, 0xB9, 0xFD, 0xFF, 0xFF, 0xFF, 0x99, 0xF7, 0xF9, 0x59],
+    ),
+];
+
+#[test]
+fn dividend_width_follows_the_level() {
+    for (i, (code, reel, protege)) in NIVEAUX.into_iter().enumerate() {
+        let emis = deux_modes(&format!("division-{i}"), code);
+        assert_eq!(emis, (reel.to_vec(), protege.to_vec()), "{code}");
+    }
+}
+
+#[test]
+fn byte_division_needs_a_byte_divisor() {
+    let erreur = octets("diviseur-si", "wep %ka, %si", true).unwrap_err();
+    assert!(erreur.contains("has no 8-bit form for %si"), "{erreur}");
+    let erreur = octets("diviseur-da", "wep %ka, %da", true).unwrap_err();
+    assert!(erreur.contains("it holds the remainder"), "{erreur}");
+    assert!(
+        erreur.contains("move the divisor into %ba or %ib"),
+        "{erreur}"
+    );
+}
+
+// L'entrée de chaque routine : PUSHAD, CALL vers le canal, puis ADD EDI, canal
+const DIV128: &[u8] = &[0x60, 0xE8, 0x10, 0, 0, 0, 0x83, 0xC7, 0x08];
+const IDIV128: &[u8] = &[0x60, 0xE8, 0x21, 0, 0, 0, 0x83, 0xC7, 0x08];
+const DIV256: &[u8] = &[0x60, 0xE8, 0x10, 0, 0, 0, 0x83, 0xC7, 0x10];
+const IDIV256: &[u8] = &[0x60, 0xE8, 0x2F, 0, 0, 0, 0x83, 0xC7, 0x10];
+
+#[test]
+fn helix_divisions_are_emitted_on_demand() {
+    let dossier = atelier("divisions");
+    let cas: [(&str, &[u8]); 4] = [
+        ("wep %eka, %eib", DIV128),
+        ("wep_ankh %eka, %eib", IDIV128),
+        ("wep %xka, 1:3", DIV256),
+        ("wep_ankh %xka, %xba", IDIV256),
+    ];
+    for (code, routine) in cas {
+        let sortie = thot(&dossier, &noyau(&format!("    {code}"), ""), &[]);
+        assert!(sortie.status.success(), "{code}: {}", stderr(&sortie));
+        for (_, autre) in cas {
+            assert_eq!(
+                emet(&dossier, autre),
+                autre == routine,
+                "{code}: {autre:02X?}"
+            );
+        }
+    }
+}
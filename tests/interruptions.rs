@@ -0,0 +1,76 @@
+// nis, iwi, khetem, wen et hotep, puis l'IDT que 'nehes' remplit : chaque entrée est une
+// porte d'interruption 32 bits vers son gestionnaire, le Phénix pour les autres.
+mod common;
+
+use common::{atelier, deux_modes, emet, noyau, stderr, thot};
+use std::fs;
+
+#[test]
+fn interrupt_verbs() {
+    let cas: [(&str, &[u8]); 6] = [
+        ("nis 0x21", &[0xCD, 0x21]),
+        ("nis 3", &[0xCD, 0x03]),
+        ("iwi", &[0xCF]),
+        ("khetem", &[0xFA]),
+        ("wen", &[0xFB]),
+        ("hotep", &[0xF4]),
+    ];
+    // Aucun préfixe : les deux modes émettent les mêmes octets
+    for (code, attendu) in cas {
+        let emis = deux_modes(&format!("interruption-{code}"), code);
+        assert_eq!(emis, (attendu.to_vec(), attendu.to_vec()), "{code}");
+    }
+}
+
+// Un gestionnaire, et son adresse posée en clair (hsesh) pour la retrouver dans l'IDT
+fn gestionnaire(vecteur: &str, avant: &str) -> String {
+    noyau(
+        &format!("{avant}    hsesh clavier\n    sesh 0xF1, 0xF2, 0xF3, 0xF4"),
+        &format!("clavier:\n    iwi\n    nehes {vecteur}, clavier"),
+    )
+}
+
+#[test]
+fn idt_entry_points_at_the_handler() {
+    let dossier = atelier("idt");
+    let sortie = thot(&dossier, &gestionnaire("0x21", ""), &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    let binaire = fs::read(dossier.join("main.bin")).unwrap();
+    let borne = binaire
+        .windows(4)
+        .position(|w| w == [0xF1, 0xF2, 0xF3, 0xF4])
+        .unwrap();
+    let adresse = &binaire[borne - 4..borne];
+    // Offset bas, sélecteur 0x08, zéro, porte 32 bits présente (0x8E), offset haut
+    let porte = [
+        adresse[0], adresse[1], 0x08, 0x00, 0x00, 0x8E, adresse[2], adresse[3],
+    ];
+    assert!(emet(&dossier, &porte));
+    // 34 entrées jusqu'au vecteur 0x21 : la limite de l'IDTR vaut 34 * 8 - 1
+    let idt = binaire.windows(8).position(|w| w == porte).unwrap() - 0x21 * 8;
+    let idtr = idt + 34 * 8;
+    assert_eq!(binaire[idtr..idtr + 2], (34u16 * 8 - 1).to_le_bytes());
+}
+
+#[test]
+fn high_vector_that_overflows_stage_two() {
+    // Le même noyau tient avec le vecteur 0x21, mais 0xFF porte l'IDT à 2048 octets
+    let dossier = atelier("idt-haute");
+    let sortie = thot(&dossier, &gestionnaire("0x21", "    shu 6000\n"), &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+
+    let sortie = thot(&dossier, &gestionnaire("0xFF", "    shu 6000\n"), &[]);
+    assert!(!sortie.status.success());
+    let message = stderr(&sortie);
+    assert!(message.contains("error[layout]"), "{message}");
+    assert!(
+        message.contains("the IDT grows to 256 entries (2048 bytes) for vector 255"),
+        "{message}"
+    );
+    // L'erreur pointe le 'nehes' du vecteur 0xFF
+    assert!(message.contains("--> main.maat:10:5"), "{message}");
+    assert!(
+        message.contains("a lower vector keeps the IDT small"),
+        "{message}"
+    );
+}
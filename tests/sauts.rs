@@ -0,0 +1,70 @@
+// wah, les sauts conditionnels et renpet : rel16 en Mode Réel, rel32 en Mode Protégé,
+// tous posés par le patch des sauts.
+mod common;
+
+use common::{deux_modes, octets};
+
+const SAUTS: [(&str, u8); 12] = [
+    ("hery", 0x87),       // JA
+    ("khery", 0x82),      // JB
+    ("hery_ankh", 0x83),  // JAE
+    ("khery_ankh", 0x86), // JBE
+    ("fai", 0x82),        // JC
+    ("nen_fai", 0x83),    // JNC
+    ("bah", 0x80),        // JO
+    ("nen_bah", 0x81),    // JNO
+    ("tep", 0x88),        // JS
+    ("nen_tep", 0x89),    // JNS
+    ("mety", 0x8A),       // JP
+    ("nen_mety", 0x8B),   // JNP
+];
+
+#[test]
+fn conditional_jumps() {
+    // Chaque saut vise le label qui le suit (distance nulle), puis un label plus haut
+    let mut code = String::from("haut:\n");
+    let (mut reel, mut protege) = (Vec::new(), Vec::new());
+    for (i, (verbe, condition)) in SAUTS.into_iter().enumerate() {
+        code += &format!("    {verbe} s{i}\ns{i}:\n");
+        reel.extend_from_slice(&[0x0F, condition, 0, 0]);
+        protege.extend_from_slice(&[0x0F, condition, 0, 0, 0, 0]);
+    }
+    code += "    tep haut";
+    let retour = -(reel.len() as i16 + 4);
+    reel.extend_from_slice(&[0x0F, 0x88]);
+    reel.extend_from_slice(&retour.to_le_bytes());
+    let retour = -(protege.len() as i32 + 6);
+    protege.extend_from_slice(&[0x0F, 0x88]);
+    protege.extend_from_slice(&retour.to_le_bytes());
+    assert_eq!(deux_modes("sauts", &code), (reel, protege));
+}
+
+#[test]
+fn renpet_counts_on_ecx() {
+    // LOOP saute par-dessus un JMP court vers le JMP proche qui atteint la cible ;
+    // en Mode Réel, 0x67 fait compter ECX
+    let code = "boucle:\n    wah %hba\n    renpet boucle";
+    let reel = vec![
+        0x66, 0x43, 0x67, 0xE2, 0x02, 0xEB, 0x03, 0xE9, 0xF6, 0xFF, // -10
+    ];
+    let protege = vec![
+        0x43, 0xE2, 0x02, 0xEB, 0x05, 0xE9, 0xF6, 0xFF, 0xFF, 0xFF, // -10
+    ];
+    assert_eq!(deux_modes("renpet", code), (reel, protege));
+}
+
+#[test]
+fn wah_width_follows_the_level() {
+    let cas: [(&str, &[u8], &[u8]); 4] = [
+        ("wah %ka", &[0xFE, 0xC0], &[0xFE, 0xC0]),
+        ("wah %ba", &[0xFE, 0xC3], &[0xFE, 0xC3]),
+        ("wah %mib", &[0x41], &[0x66, 0x41]),
+        ("wah %hdi", &[0x66, 0x47], &[0x47]),
+    ];
+    for (i, (code, reel, protege)) in cas.into_iter().enumerate() {
+        let emis = deux_modes(&format!("wah-{i}"), code);
+        assert_eq!(emis, (reel.to_vec(), protege.to_vec()), "{code}");
+    }
+    let erreur = octets("wah-si", "wah %si", true).unwrap_err();
+    assert!(erreur.contains("has no 8-bit form for %si"), "{erreur}");
+}
@@ -0,0 +1,129 @@
+// La bibliothèque standard embarquée : chaque tablette se tisse avec `dema <std/...>`
+// et se compile dans un vrai noyau. On lance le binaire thot, comme un utilisateur.
+mod common;
+
+use common::{atelier, emet, noyau, stderr, thot};
+use std::fs;
+
+const TABLETTES: [&str; 5] = ["delay", "format", "keyboard", "memory", "print"];
+
+#[test]
+fn every_tablet_compiles_in_a_kernel() {
+    // Pour chaque tablette, une suite d'octets que son code doit émettre
+    let signatures: [&[u8]; 5] = [
+        // delay : renpet .tour (LOOP, JMP court, JMP vers lui-même)
+        &[0xE2, 0x02, 0xEB, 0x05, 0xE9, 0xF7, 0xFF, 0xFF, 0xFF],
+        // format : wep %hka, 10
+        &[0x51, 0xB9, 10, 0, 0, 0, 0x31, 0xD2, 0xF7, 0xF1, 0x59],
+        // keyboard : wdj %hka, touches puis hery_ankh (CMP EAX, 58 ; JAE)
+        &[0x81, 0xF8, 58, 0, 0, 0, 0x0F, 0x83],
+        // memory : tut (CLD ; REP MOVSB)
+        &[0xFC, 0xF3, 0xA4],
+        // print : wep %hka, colonnes
+        &[0x51, 0xB9, 80, 0, 0, 0, 0x31, 0xD2, 0xF7, 0xF1, 0x59],
+    ];
+    for (tablette, signature) in TABLETTES.into_iter().zip(signatures) {
+        let dossier = atelier(tablette);
+        let sortie = thot(&dossier, &noyau("", &format!("dema <std/{tablette}>")), &[]);
+        assert!(
+            sortie.status.success(),
+            "std/{tablette}: {}",
+            stderr(&sortie)
+        );
+        assert!(
+            emet(&dossier, signature),
+            "std/{tablette}: {signature:02X?}"
+        );
+    }
+}
+
+#[test]
+fn exported_routines_are_callable() {
+    let dossier = atelier("exports");
+    let appels = "
+    henek %hka, -42
+    jena print_dec
+    jena print_hex
+    jena newline
+    henek %ka, 'A'
+    jena print_char
+    henek %hdi, 0x20000
+    henek %hib, 16
+    jena read_line
+    jena memcpy
+    jena memset
+    henek %hka, 0x30000
+    henek %hib, 8
+    jena page_init
+    jena page_alloc
+    jena page_free
+    jena delay
+    jena wait_cycles";
+    let demas: Vec<String> = TABLETTES
+        .iter()
+        .map(|t| format!("dema <std/{t}>"))
+        .collect();
+    let sortie = thot(&dossier, &noyau(appels, &demas.join("\n")), &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+
+    // print_dec divise par dix : PUSH ECX, MOV ECX 10, XOR EDX EDX, DIV ECX, POP ECX
+    assert!(emet(
+        &dossier,
+        &[0x51, 0xB9, 10, 0, 0, 0, 0x31, 0xD2, 0xF7, 0xF1, 0x59]
+    ));
+}
+
+#[test]
+fn namespaced_import() {
+    let dossier = atelier("alias");
+    let code = noyau(
+        "    henek %hsi, 0\n    jena io.print_string",
+        "dema <std/print> as io",
+    );
+    let sortie = thot(&dossier, &code, &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+}
+
+#[test]
+fn internal_helpers_stay_private() {
+    let dossier = atelier("private");
+    let code = noyau("    sena %hka, curseur", "dema <std/keyboard>");
+    let sortie = thot(&dossier, &code, &[]);
+    assert!(!sortie.status.success());
+    assert!(stderr(&sortie).contains("'curseur'"));
+}
+
+#[test]
+fn unknown_tablet_lists_the_library() {
+    let dossier = atelier("inconnue");
+    let sortie = thot(&dossier, &noyau("", "dema <std/nope>"), &[]);
+    assert!(!sortie.status.success());
+    let message = stderr(&sortie);
+    assert!(message.contains("The standard library has no tablet 'std/nope'"));
+    assert!(message.contains("std/print"));
+}
+
+#[test]
+fn version_is_visible_to_conditions() {
+    let dossier = atelier("version");
+    let code = noyau(
+        "if THOT_STD_VERSION >= 1\n    jena newline\nelse\n    jena absente\nend",
+        "dema <std/print>",
+    );
+    let sortie = thot(&dossier, &code, &[]);
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+}
+
+#[test]
+fn embedded_tablets_are_not_file_dependencies() {
+    let dossier = atelier("depfile");
+    let sortie = thot(
+        &dossier,
+        &noyau("", "dema <std/delay>"),
+        &["--dep-file", "main.d"],
+    );
+    assert!(sortie.status.success(), "{}", stderr(&sortie));
+    let regle = fs::read_to_string(dossier.join("main.d")).unwrap();
+    assert!(regle.starts_with("main.bin: \\\n  main.maat\n"));
+    assert!(!regle.contains("std"));
+}